@@ -0,0 +1,50 @@
+//! Requests to the setlistrs server, sent by `setlistrs_client`. Its base
+//! URL is taken from `config.json` served next to the app, e.g.
+//! `{"api_url": "https://api.example.com"}`, or from `SETLISTRS_API_URL` set
+//! when the app was built with Trunk.
+
+use std::sync::OnceLock;
+
+use gloo_net::http::Request;
+use serde::Deserialize;
+use setlistrs_client::transport::GlooTransport;
+use setlistrs_client::Client;
+
+const DEFAULT_BASE_URL: &str = "http://127.0.0.1:8081";
+
+static BASE_URL: OnceLock<String> = OnceLock::new();
+
+#[derive(Deserialize)]
+struct Config {
+    api_url: Option<String>,
+}
+
+/// Has to be awaited before the app is rendered, `config.json` is optional.
+pub async fn load_config() {
+    let config = match Request::get("/config.json").send().await {
+        Ok(response) if response.ok() => response.json::<Config>().await.ok(),
+        _ => None,
+    };
+    if let Some(api_url) = config.and_then(|config| config.api_url) {
+        let _ = BASE_URL.set(api_url);
+    }
+}
+
+pub fn base_url() -> &'static str {
+    match BASE_URL.get() {
+        Some(base_url) => base_url,
+        None => option_env!("SETLISTRS_API_URL").unwrap_or(DEFAULT_BASE_URL),
+    }
+}
+
+/// Client of the server, the browser sends session cookie along with its
+/// requests.
+pub fn client() -> Client<GlooTransport> {
+    Client::new(base_url(), GlooTransport)
+}
+
+/// Absolute URL of API `path`, e.g. for links to PDFs of shared setlists.
+/// Library PDFs have their own URLs in the client, which keep `?band=`.
+pub fn url(path: &str) -> String {
+    client().url(path)
+}
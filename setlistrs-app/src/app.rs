@@ -1,7 +1,11 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-use crate::components::{SetlistDetails, SetlistList, SongAdd, SongsList};
+use crate::api;
+use crate::components::{
+    use_notifier, NotificationsProvider, SetlistDetails, SetlistList, SharedSetlist, SongAdd,
+    SongDetails, SongEdit, SongTrash, SongsList, UserLogin, UserRegister,
+};
 
 #[derive(Clone, Routable, PartialEq)]
 pub enum Route {
@@ -9,10 +13,22 @@ pub enum Route {
     SongList,
     #[at("/add-song")]
     SongAdd,
+    #[at("/trash")]
+    SongTrash,
+    #[at("/songs/:id/edit")]
+    SongEdit { id: i64 },
+    #[at("/songs/:id")]
+    SongDetails { id: i64 },
     #[at("/setlists/:id")]
     SetlistDetails { id: i64 },
     #[at("/setlists")]
     SetlistList,
+    #[at("/shared/setlists/:token")]
+    SharedSetlist { token: String },
+    #[at("/login")]
+    UserLogin,
+    #[at("/register")]
+    UserRegister,
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -22,8 +38,14 @@ fn switch(route: Route) -> Html {
     match route {
         Route::SongList => html! { <SongsList /> },
         Route::SongAdd => html! { <SongAdd/> },
+        Route::SongEdit { id } => html! { <SongEdit seed={id} /> },
+        Route::SongDetails { id } => html! { <SongDetails seed={id} /> },
+        Route::SongTrash => html! { <SongTrash /> },
         Route::SetlistDetails { id } => html! { <SetlistDetails seed={id} /> },
         Route::SetlistList => html! { <SetlistList /> },
+        Route::SharedSetlist { token } => html! { <SharedSetlist token={token} /> },
+        Route::UserLogin => html! { <UserLogin /> },
+        Route::UserRegister => html! { <UserRegister /> },
         Route::NotFound => html! { <h1> {"404"} </h1> },
     }
 }
@@ -56,11 +78,55 @@ pub fn nav_items() -> Html {
         }
     };
 
+    let trash_button = {
+        let navigator = navigator.clone();
+        let onclick = Callback::from(move |_| navigator.push(&Route::SongTrash));
+        html! {
+            <button class="secondary" {onclick}>{ "Trash" }</button>
+        }
+    };
+
+    let login_button = {
+        let navigator = navigator.clone();
+        let onclick = Callback::from(move |_| navigator.push(&Route::UserLogin));
+        html! {
+            <button class="secondary" {onclick}>{ "Login" }</button>
+        }
+    };
+
+    let register_button = {
+        let navigator = navigator.clone();
+        let onclick = Callback::from(move |_| navigator.push(&Route::UserRegister));
+        html! {
+            <button class="secondary" {onclick}>{ "Register" }</button>
+        }
+    };
+
+    let logout_button = {
+        let notifier = use_notifier();
+        let onclick = Callback::from(move |_| {
+            let navigator = navigator.clone();
+            let notifier = notifier.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if notifier.ok(api::client().logout().await).is_some() {
+                    navigator.push(&Route::UserLogin);
+                }
+            });
+        });
+        html! {
+            <button class="secondary" {onclick}>{ "Logout" }</button>
+        }
+    };
+
     html! {
         <ul>
             <li>{ all_songs_list_button }</li>
             <li>{ add_song_button }</li>
             <li>{ setlist_list }</li>
+            <li>{ trash_button }</li>
+            <li>{ login_button }</li>
+            <li>{ register_button }</li>
+            <li>{ logout_button }</li>
         </ul>
     }
 }
@@ -69,17 +135,19 @@ pub fn nav_items() -> Html {
 pub fn app() -> Html {
     html! {
         <BrowserRouter>
-            <header class={classes!("container")}>
-                <hgroup>
-                    <h1>{ "setlistrs" }</h1>
-                </hgroup>
-                <nav>
-                    <NavItems />
-                </nav>
-            </header>
-            <main class={classes!("container")}>
-                <Switch<Route> render={switch} />
-            </main>
+            <NotificationsProvider>
+                <header class={classes!("container")}>
+                    <hgroup>
+                        <h1>{ "setlistrs" }</h1>
+                    </hgroup>
+                    <nav>
+                        <NavItems />
+                    </nav>
+                </header>
+                <main class={classes!("container")}>
+                    <Switch<Route> render={switch} />
+                </main>
+            </NotificationsProvider>
         </BrowserRouter>
     }
 }
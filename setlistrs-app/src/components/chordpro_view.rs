@@ -0,0 +1,121 @@
+use setlistrs_types::chordpro::{self, ChordProSong, Line, Section, SectionKind, Segment};
+use yew::{classes, function_component, html, AttrValue, Html, Properties};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct ChordProViewProps {
+    pub body: AttrValue,
+    /// Show title, artist, key, capo and tempo above the lyrics.
+    #[prop_or(true)]
+    pub show_header: bool,
+}
+
+#[function_component(ChordProView)]
+pub fn chordpro_view(ChordProViewProps { body, show_header }: &ChordProViewProps) -> Html {
+    let song = match chordpro::parse(body) {
+        Ok(song) => song,
+        Err(e) => {
+            return html! {
+                <div class={classes!("chordpro")}>
+                    <p class={classes!("chordpro-error")}>{ format!("Can not display lyrics, {}", e) }</p>
+                    <pre>{ body }</pre>
+                </div>
+            }
+        }
+    };
+
+    html! {
+        <div class={classes!("chordpro")}>
+            if *show_header {
+                { header(&song) }
+            }
+            { for song.sections.iter().map(section) }
+        </div>
+    }
+}
+
+fn header(song: &ChordProSong) -> Html {
+    let meta: Vec<String> = [
+        song.key.as_ref().map(|key| format!("Key: {}", key)),
+        song.capo.map(|capo| format!("Capo: {}", capo)),
+        song.tempo.map(|tempo| format!("Tempo: {}", tempo)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    html! {
+        <>
+            if let Some(title) = &song.title {
+                <h3 class={classes!("chordpro-title")}>{ title }</h3>
+            }
+            if let Some(subtitle) = song.subtitle.as_ref().or(song.artist.as_ref()) {
+                <p class={classes!("chordpro-subtitle")}>{ subtitle }</p>
+            }
+            if !meta.is_empty() {
+                <p class={classes!("chordpro-meta")}>{ meta.join(" · ") }</p>
+            }
+        </>
+    }
+}
+
+fn section(section: &Section) -> Html {
+    let label = section.label.clone().or_else(|| match section.kind {
+        SectionKind::Chorus => Some("Chorus".to_string()),
+        SectionKind::Bridge => Some("Bridge".to_string()),
+        _ => None,
+    });
+
+    let content = if section.kind == SectionKind::Tab {
+        let tab: Vec<&str> = section
+            .lines
+            .iter()
+            .filter_map(|line| match line {
+                Line::Preformatted(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        html! { <pre>{ tab.join("\n") }</pre> }
+    } else {
+        html! { for section.lines.iter().map(line) }
+    };
+
+    html! {
+        <section class={classes!("chordpro-section", format!("chordpro-{}", section.kind.name()))}>
+            if let Some(label) = label {
+                <h5 class={classes!("chordpro-label")}>{ label }</h5>
+            }
+            { content }
+        </section>
+    }
+}
+
+fn line(line: &Line) -> Html {
+    match line {
+        Line::Lyrics(segments) => {
+            let has_chords = segments.iter().any(|segment| segment.chord.is_some());
+            html! {
+                <div class={classes!("chordpro-line")}>
+                    { for segments.iter().map(|segment| self::segment(segment, has_chords)) }
+                </div>
+            }
+        }
+        Line::Comment(comment) => html! { <p class={classes!("chordpro-comment")}>{ comment }</p> },
+        Line::Preformatted(text) => html! { <pre>{ text }</pre> },
+        Line::Empty => html! { <div class={classes!("chordpro-empty")}></div> },
+    }
+}
+
+fn segment(segment: &Segment, has_chords: bool) -> Html {
+    html! {
+        <span class={classes!("chordpro-segment")}>
+            if has_chords {
+                <span class={classes!("chordpro-chord")}>
+                    { segment.chord.clone().unwrap_or_else(|| "\u{a0}".to_string()) }
+                </span>
+            }
+            <span class={classes!("chordpro-lyrics")}>
+                { if segment.lyrics.is_empty() { "\u{a0}" } else { segment.lyrics.as_str() } }
+            </span>
+        </span>
+    }
+}
@@ -1,11 +1,35 @@
+mod chordpro_view;
+mod notifications;
 mod setlist_add;
 mod setlist_details;
+mod setlist_gig_details;
 mod setlist_list;
+mod setlist_shares;
+mod setlist_song_overrides;
+mod setlist_song_picker;
+mod shared_setlist;
 mod song_add;
+mod song_details;
+mod song_edit;
 mod song_list;
+mod song_trash;
+mod user_login;
+mod user_register;
 
+pub use chordpro_view::*;
+pub use notifications::*;
 pub use setlist_add::*;
 pub use setlist_details::*;
+pub use setlist_gig_details::*;
 pub use setlist_list::*;
+pub use setlist_shares::*;
+pub use setlist_song_overrides::*;
+pub use setlist_song_picker::*;
+pub use shared_setlist::*;
 pub use song_add::*;
+pub use song_details::*;
+pub use song_edit::*;
 pub use song_list::*;
+pub use song_trash::*;
+pub use user_login::*;
+pub use user_register::*;
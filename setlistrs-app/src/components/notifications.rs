@@ -0,0 +1,194 @@
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use gloo_timers::callback::Timeout;
+use setlistrs_client::Error;
+use yew::prelude::*;
+
+const SUCCESS_TIMEOUT_MS: u32 = 4_000;
+const ERROR_TIMEOUT_MS: u32 = 8_000;
+/// Long enough to click the action, e.g. undo.
+const ACTION_TIMEOUT_MS: u32 = 8_000;
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    Success,
+    Error,
+}
+
+/// Button shown next to the message, e.g. to undo what was just done.
+#[derive(Clone, PartialEq)]
+pub struct NotificationAction {
+    pub label: String,
+    pub on_click: Callback<()>,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Notification {
+    id: u32,
+    kind: NotificationKind,
+    message: String,
+    action: Option<NotificationAction>,
+}
+
+#[derive(Default, PartialEq)]
+pub struct Notifications {
+    list: Vec<Notification>,
+}
+
+pub enum NotificationsAction {
+    Show(Notification),
+    Dismiss(u32),
+}
+
+impl Reducible for Notifications {
+    type Action = NotificationsAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut list = self.list.clone();
+        match action {
+            NotificationsAction::Show(notification) => list.push(notification),
+            NotificationsAction::Dismiss(id) => list.retain(|notification| notification.id != id),
+        }
+
+        Notifications { list }.into()
+    }
+}
+
+/// Shows toasts of `NotificationsProvider`, get it with `use_notifier`.
+#[derive(Clone, PartialEq)]
+pub struct Notifier {
+    notifications: UseReducerDispatcher<Notifications>,
+}
+
+impl Notifier {
+    pub fn success(&self, message: impl Into<String>) {
+        self.show(
+            NotificationKind::Success,
+            message.into(),
+            None,
+            SUCCESS_TIMEOUT_MS,
+        );
+    }
+
+    /// Success with action, toast is dismissed when the action is clicked.
+    pub fn success_with_action(&self, message: impl Into<String>, action: NotificationAction) {
+        self.show(
+            NotificationKind::Success,
+            message.into(),
+            Some(action),
+            ACTION_TIMEOUT_MS,
+        );
+    }
+
+    pub fn error(&self, message: impl Into<String>) {
+        self.show(
+            NotificationKind::Error,
+            message.into(),
+            None,
+            ERROR_TIMEOUT_MS,
+        );
+    }
+
+    /// Value of successful request. Errors are shown and give `None`.
+    pub fn ok<T>(&self, result: Result<T, Error>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.error(e.to_string());
+                None
+            }
+        }
+    }
+
+    fn show(
+        &self,
+        kind: NotificationKind,
+        message: String,
+        action: Option<NotificationAction>,
+        timeout_ms: u32,
+    ) {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        self.notifications
+            .dispatch(NotificationsAction::Show(Notification {
+                id,
+                kind,
+                message,
+                action,
+            }));
+
+        let notifications = self.notifications.clone();
+        Timeout::new(timeout_ms, move || {
+            notifications.dispatch(NotificationsAction::Dismiss(id))
+        })
+        .forget();
+    }
+}
+
+#[hook]
+pub fn use_notifier() -> Notifier {
+    use_context::<Notifier>().expect("Component has to be inside of NotificationsProvider.")
+}
+
+#[derive(PartialEq, Properties)]
+pub struct NotificationsProviderProps {
+    pub children: Children,
+}
+
+/// App wide toasts, e.g. with error responses of the server.
+#[function_component(NotificationsProvider)]
+pub fn notifications_provider(
+    NotificationsProviderProps { children }: &NotificationsProviderProps,
+) -> Html {
+    let notifications = use_reducer(Notifications::default);
+    let notifier = Notifier {
+        notifications: notifications.dispatcher(),
+    };
+
+    html! {
+        <ContextProvider<Notifier> context={notifier}>
+            { for children.iter() }
+            <div class={classes!("toasts")}>
+            {
+                for notifications.list.iter().map(|notification| {
+                    let on_dismiss_click = {
+                        let notifications = notifications.dispatcher();
+                        let id = notification.id;
+                        Callback::from(move |e: MouseEvent| {
+                            e.prevent_default();
+                            notifications.dispatch(NotificationsAction::Dismiss(id))
+                        })
+                    };
+                    let action = notification.action.as_ref().map(|action| {
+                        let notifications = notifications.dispatcher();
+                        let id = notification.id;
+                        let on_click = action.on_click.clone();
+                        let on_action_click = Callback::from(move |e: MouseEvent| {
+                            e.prevent_default();
+                            notifications.dispatch(NotificationsAction::Dismiss(id));
+                            on_click.emit(());
+                        });
+                        (action.label.clone(), on_action_click)
+                    });
+                    let kind_class = match notification.kind {
+                        NotificationKind::Success => "toast-success",
+                        NotificationKind::Error => "toast-error",
+                    };
+
+                    html! {
+                        <article class={classes!("toast", kind_class)} role="alert">
+                            <span>{ &notification.message }</span>
+                            if let Some((label, on_action_click)) = action {
+                                <a href="#" class={classes!("toast-action")} onclick={on_action_click}>{ label }</a>
+                            }
+                            <a href="#" title="Dismiss" onclick={on_dismiss_click}>{"×"}</a>
+                        </article>
+                    }
+                })
+            }
+            </div>
+        </ContextProvider<Notifier>>
+    }
+}
@@ -1,11 +1,12 @@
-use gloo_net::http::Request;
-use setlistrs_types::NewSetlist;
+use setlistrs_types::{GigDetails, NewSetlist};
 use web_sys::{FormData, SubmitEvent};
 use yew::callback::Callback;
 use yew::{function_component, html, Html, Properties, Reducible, TargetCast};
 use yew_router::prelude::use_navigator;
 
+use crate::api;
 use crate::app::Route;
+use crate::components::use_notifier;
 
 pub enum SongChockboxAction {
     Add(i64),
@@ -46,11 +47,13 @@ pub struct SetlistAddProps {
 #[function_component(SetlistAdd)]
 pub fn setlist_add(props: &SetlistAddProps) -> Html {
     let navigator = use_navigator().expect("There is no reason it will not work.");
+    let notifier = use_notifier();
     let on_setlist_submit = {
         let songs_for_setlist = props.songs.clone();
 
         Callback::from(move |e: SubmitEvent| {
             let navigator = navigator.clone();
+            let notifier = notifier.clone();
             e.prevent_default();
             let form_data = FormData::new_with_form(&e.target_unchecked_into())
                 .expect("This is going to work since we are sure form exists.");
@@ -60,26 +63,26 @@ pub fn setlist_add(props: &SetlistAddProps) -> Html {
                     Some(display_title) => display_title,
                     None => panic!(),
                 },
+                gig: GigDetails {
+                    gig_date: form_data
+                        .get("gig_date")
+                        .as_string()
+                        .filter(|gig_date| !gig_date.is_empty()),
+                    venue: form_data
+                        .get("venue")
+                        .as_string()
+                        .filter(|venue| !venue.is_empty()),
+                    ..Default::default()
+                },
                 songs: songs_for_setlist.clone(),
             };
 
             wasm_bindgen_futures::spawn_local(async move {
-                let response = Request::post("http://127.0.0.1:8081/setlists")
-                    .json(&new_setlist)
-                    .expect("This will work")
-                    .send()
-                    .await;
-
-                match response {
-                    Ok(possible_setlist_id) => {
-                        let setlist_id: Result<i64, _> = possible_setlist_id.json().await;
-                        match setlist_id {
-                            Ok(id) => navigator.push(&Route::SetlistDetails { id }),
-                            Err(_) => panic!(),
-                        }
-                    }
-                    Err(_) => panic!(),
-                };
+                let result = api::client().create_setlist(&new_setlist).await;
+                if let Some(id) = notifier.ok(result) {
+                    notifier.success("Setlist created");
+                    navigator.push(&Route::SetlistDetails { id });
+                }
             });
         })
     };
@@ -88,6 +91,16 @@ pub fn setlist_add(props: &SetlistAddProps) -> Html {
             <fieldset disabled={ props.disabled }>
                 <label for="display_title">{"Setlist name"}</label>
                 <input type="text" name="display_title" id="display_title"/>
+                <div class="grid">
+                    <label for="gig_date">
+                        {"Gig date"}
+                        <input type="date" name="gig_date" id="gig_date"/>
+                    </label>
+                    <label for="venue">
+                        {"Venue"}
+                        <input type="text" name="venue" id="venue"/>
+                    </label>
+                </div>
                 <button type="submit">
                     {"Create setlist from checked songs"}
                 </button>
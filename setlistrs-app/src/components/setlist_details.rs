@@ -1,6 +1,18 @@
-use gloo_net::http::Request;
-use setlistrs_types::Setlist;
-use yew::{function_component, html, use_effect_with_deps, use_state, AttrValue, Html, Properties};
+use std::rc::Rc;
+
+use setlistrs_types::{
+    GigDetails, NewSetlistSong, Setlist, SetlistOrder, SetlistSongOverrides, SetlistSummary,
+};
+use yew::{
+    classes, function_component, html, use_effect_with_deps, use_reducer, use_state, AttrValue,
+    Callback, DragEvent, Html, MouseEvent, Properties, Reducible,
+};
+
+use crate::api;
+use crate::components::{
+    use_notifier, ChordProView, SetlistGigDetails, SetlistShares, SetlistSongOverridesForm,
+    SetlistSongPicker,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq, Properties)]
 pub struct Props {
@@ -11,81 +23,398 @@ pub struct Props {
 pub fn setlist_details(props: &Props) -> Html {
     let setlist = use_state(|| None);
     let setlist_id = props.seed;
+    let notifier = use_notifier();
     {
         let setlist = setlist.clone();
         use_effect_with_deps(
-            move |_| {
+            move |setlist_id| {
+                let setlist_id = *setlist_id;
                 let setlist = setlist.clone();
-                let setlist_id = setlist_id.clone();
+                let notifier = notifier.clone();
+                // Previous setlist is not shown while another one loads.
+                setlist.set(None);
 
                 wasm_bindgen_futures::spawn_local(async move {
-                    let response = Request::get(
-                        format!("http://127.0.0.1:8081/setlists/{}", setlist_id).as_str(),
-                    )
-                    .send()
-                    .await;
-                    match response {
-                        Ok(response) => {
-                            let possible_setlist: Result<Setlist, _> = response.json().await;
-                            match possible_setlist {
-                                Ok(s) => setlist.set(Some(s)),
-                                Err(_) => panic!(), // TODO -> handle it gracefuly
-                            }
-                        }
-                        Err(_) => panic!(), // TODO -> handle it gracefuly
-                    };
+                    if let Some(s) = notifier.ok(api::client().get_setlist(setlist_id).await) {
+                        setlist.set(Some(s));
+                    }
                 });
 
                 || ()
             },
-            (),
+            setlist_id,
         );
     }
 
     html! {
         match &*setlist {
             Some(setlist) => {
-                let songs: Vec<(AttrValue, AttrValue)> = setlist.songs
-                    .iter()
-                    .map(|(_song_id, song)| (
-                            AttrValue::from(song.display_title.clone()),
-                            AttrValue::from(song.chords.clone())
-                            )
-                        )
-                    .collect();
-                html! { <SetlistDetailsContent display_title={setlist.display_title.clone()} songs={songs} />}
+                html! {
+                    // Keyed, so state of the previous setlist is not kept.
+                    <SetlistDetailsContent
+                        key={setlist_id}
+                        setlist_id={setlist_id}
+                        display_title={setlist.display_title.clone()}
+                        gig={setlist.gig.clone()}
+                        songs={SetlistDetailsSong::list_from(setlist)}
+                    />
+                }
             },
             None=> html! { <p> { "Fetching data..." } </p> }
         }
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Properties)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SetlistDetailsSong {
+    pub id: i64,
+    pub display_title: AttrValue,
+    pub chords: AttrValue,
+    pub chordpro: Option<AttrValue>,
+    pub overrides: SetlistSongOverrides,
+    pub deleted: bool,
+}
+
+impl SetlistDetailsSong {
+    pub fn list_from(setlist: &Setlist) -> Vec<Self> {
+        setlist
+            .songs
+            .iter()
+            .map(|(song_id, song)| Self {
+                id: *song_id,
+                display_title: AttrValue::from(song.display_title.clone()),
+                chords: AttrValue::from(song.chords.clone()),
+                chordpro: song.chordpro.clone().map(AttrValue::from),
+                overrides: song.overrides.clone(),
+                deleted: song.deleted,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
 pub struct SetlistDetailsContentProps {
+    pub setlist_id: i64,
     pub display_title: AttrValue,
-    pub songs: Vec<(AttrValue, AttrValue)>,
+    pub gig: GigDetails,
+    pub songs: Vec<SetlistDetailsSong>,
+}
+
+enum SetlistSongsAction {
+    Set(Vec<SetlistDetailsSong>),
+    Remove(i64),
+}
+
+struct SetlistSongs {
+    list: Vec<SetlistDetailsSong>,
+}
+
+impl Reducible for SetlistSongs {
+    type Action = SetlistSongsAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            SetlistSongsAction::Set(list) => SetlistSongs { list }.into(),
+            SetlistSongsAction::Remove(song_id) => {
+                let list = self
+                    .list
+                    .iter()
+                    .filter(|song| song.id != song_id)
+                    .cloned()
+                    .collect();
+
+                SetlistSongs { list }.into()
+            }
+        }
+    }
 }
+
 #[function_component(SetlistDetailsContent)]
 pub fn setlist_details_content(props: &SetlistDetailsContentProps) -> Html {
+    let songs = use_reducer(|| SetlistSongs {
+        list: props.songs.clone(),
+    });
+    let summary = use_state(|| SetlistSummary {
+        display_title: props.display_title.to_string(),
+        gig: props.gig.clone(),
+    });
+    let dragged = use_state(|| None::<usize>);
+    // Song which setlist specific chords, key and note are edited.
+    let adjusted = use_state(|| None::<i64>);
+    let notifier = use_notifier();
+
+    // Order is applied right away and rolled back when server refuses it.
+    let on_move = {
+        let songs = songs.clone();
+        let setlist_id = props.setlist_id;
+        let notifier = notifier.clone();
+        Callback::from(move |(from, to): (usize, usize)| {
+            if from == to || from >= songs.list.len() || to >= songs.list.len() {
+                return;
+            }
+
+            let previous_songs = songs.list.clone();
+            let mut reordered_songs = previous_songs.clone();
+            let song = reordered_songs.remove(from);
+            reordered_songs.insert(to, song);
+
+            let setlist_order = SetlistOrder {
+                songs: reordered_songs.iter().map(|song| song.id).collect(),
+            };
+            songs.dispatch(SetlistSongsAction::Set(reordered_songs));
+
+            let songs = songs.clone();
+            let notifier = notifier.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = api::client()
+                    .reorder_setlist(setlist_id, &setlist_order)
+                    .await;
+                if notifier.ok(result).is_none() {
+                    songs.dispatch(SetlistSongsAction::Set(previous_songs));
+                }
+            });
+        })
+    };
+
+    let on_song_pick = {
+        let songs = songs.clone();
+        let setlist_id = props.setlist_id;
+        let notifier = notifier.clone();
+        Callback::from(move |song_id: i64| {
+            let songs = songs.clone();
+            let notifier = notifier.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let new_setlist_song = NewSetlistSong {
+                    song_id,
+                    position: None,
+                };
+                let result = api::client()
+                    .add_setlist_song(setlist_id, &new_setlist_song)
+                    .await;
+                if let Some(setlist) = notifier.ok(result) {
+                    songs.dispatch(SetlistSongsAction::Set(SetlistDetailsSong::list_from(
+                        &setlist,
+                    )));
+                }
+            });
+        })
+    };
+
+    let on_overrides_update = {
+        let songs = songs.clone();
+        let adjusted = adjusted.clone();
+        Callback::from(move |setlist: Setlist| {
+            songs.dispatch(SetlistSongsAction::Set(SetlistDetailsSong::list_from(
+                &setlist,
+            )));
+            adjusted.set(None);
+        })
+    };
+    let on_overrides_cancel = {
+        let adjusted = adjusted.clone();
+        Callback::from(move |_: ()| adjusted.set(None))
+    };
+
+    let on_summary_update = {
+        let summary = summary.clone();
+        Callback::from(move |updated_summary: SetlistSummary| summary.set(updated_summary))
+    };
+
+    let songs_count = songs.list.len();
+    let song_ids: Vec<i64> = songs.list.iter().map(|song| song.id).collect();
+
     html! {
+    <>
+    <SetlistGigDetails
+        setlist_id={props.setlist_id}
+        summary={(*summary).clone()}
+        on_update={on_summary_update}
+    />
+    <article>
+        <SetlistSongPicker excluded={song_ids} on_pick={on_song_pick} />
+    </article>
+    <div class="grid">
+        <a
+            role="button"
+            target="_blank"
+            href={api::client().setlist_pdf_url(props.setlist_id, 0, false)}
+        >{"Stage sheet PDF"}</a>
+        <a
+            role="button"
+            class="secondary"
+            target="_blank"
+            href={api::client().setlist_pdf_url(props.setlist_id, 0, true)}
+        >{"PDF with chords"}</a>
+        <a
+            role="button"
+            class="secondary"
+            target="_blank"
+            href={api::client().setlist_songbook_url(props.setlist_id, 0)}
+        >{"Songbook PDF"}</a>
+    </div>
+    <SetlistShares setlist_id={props.setlist_id} />
     <table>
-        <caption> { &props.display_title } </caption>
+        <caption> { &summary.display_title } </caption>
         <thead>
+        <th>{"Order"}</th>
         <th>{"Song"}</th>
         <th>{"Chords"}</th>
+        <th>{"Action"}</th>
         </thead>
         <tbody>
         {
-            for props.songs
+            for songs
+                .list
                 .iter()
-                .map(|(display_title, chords)| html! {
-                <tr>
-                    <td>{display_title}</td>
-                    <td>{chords}</td>
-                </tr>
-            })
+                .enumerate()
+                .map(|(index, song)| {
+                    let ondragstart = {
+                        let dragged = dragged.clone();
+                        Callback::from(move |e: DragEvent| {
+                            if let Some(data_transfer) = e.data_transfer() {
+                                data_transfer.set_effect_allowed("move");
+                                // Firefox does not start dragging without any data set.
+                                let _ = data_transfer.set_data("text/plain", &index.to_string());
+                            }
+                            dragged.set(Some(index));
+                        })
+                    };
+                    let ondragover = Callback::from(|e: DragEvent| e.prevent_default());
+                    let ondrop = {
+                        let dragged = dragged.clone();
+                        let on_move = on_move.clone();
+                        Callback::from(move |e: DragEvent| {
+                            e.prevent_default();
+                            if let Some(from) = *dragged {
+                                on_move.emit((from, index));
+                            }
+                            dragged.set(None);
+                        })
+                    };
+                    let ondragend = {
+                        let dragged = dragged.clone();
+                        Callback::from(move |_: DragEvent| dragged.set(None))
+                    };
+                    let on_up_click = {
+                        let on_move = on_move.clone();
+                        Callback::from(move |e: MouseEvent| {
+                            e.prevent_default();
+                            on_move.emit((index, index.saturating_sub(1)));
+                        })
+                    };
+                    let on_remove_click = {
+                        let songs = songs.clone();
+                        let setlist_id = props.setlist_id;
+                        let song_id = song.id;
+                        let notifier = notifier.clone();
+                        Callback::from(move |e: MouseEvent| {
+                            e.prevent_default();
+                            let songs = songs.clone();
+                            let notifier = notifier.clone();
+                            wasm_bindgen_futures::spawn_local(async move {
+                                let result = api::client().remove_setlist_song(setlist_id, song_id).await;
+                                if notifier.ok(result).is_some() {
+                                    songs.dispatch(SetlistSongsAction::Remove(song_id));
+                                }
+                            });
+                        })
+                    };
+                    let on_adjust_click = {
+                        let adjusted = adjusted.clone();
+                        let song_id = song.id;
+                        Callback::from(move |e: MouseEvent| {
+                            e.prevent_default();
+                            adjusted.set((*adjusted != Some(song_id)).then_some(song_id));
+                        })
+                    };
+                    let on_down_click = {
+                        let on_move = on_move.clone();
+                        Callback::from(move |e: MouseEvent| {
+                            e.prevent_default();
+                            on_move.emit((index, index + 1));
+                        })
+                    };
+
+                    let adjustments: Vec<String> = [
+                        (song.overrides.transpose != 0)
+                            .then(|| format!("Transposed {:+}", song.overrides.transpose)),
+                        song.overrides.capo.map(|capo| format!("Capo {}", capo)),
+                        song.overrides.note.clone(),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+
+                    html! {
+                        <>
+                        <tr
+                            draggable="true"
+                            class={classes!((*dragged == Some(index)).then_some("dragged"))}
+                            {ondragstart}
+                            {ondragover}
+                            {ondrop}
+                            {ondragend}
+                        >
+                            <td class={classes!("setlist-order")}>
+                                <button
+                                    title={"Move up"}
+                                    disabled={index == 0}
+                                    onclick={on_up_click}
+                                >{"↑"}</button>
+                                <button
+                                    title={"Move down"}
+                                    disabled={index + 1 == songs_count}
+                                    onclick={on_down_click}
+                                >{"↓"}</button>
+                            </td>
+                            <td>
+                                {&song.display_title}
+                                if song.deleted {
+                                    { " " }
+                                    <mark title="Restore it from trash or remove it from setlist">{"In trash"}</mark>
+                                }
+                                if !adjustments.is_empty() {
+                                    <br/>
+                                    <small>{ adjustments.join(" · ") }</small>
+                                }
+                            </td>
+                            <td>
+                            {
+                                match &song.chordpro {
+                                    Some(chordpro) => html! {
+                                        <details>
+                                            <summary>{&song.chords}</summary>
+                                            <ChordProView body={chordpro.clone()} show_header={false} />
+                                        </details>
+                                    },
+                                    None => html! { &song.chords },
+                                }
+                            }
+                            </td>
+                            <td>
+                                <button onclick={on_adjust_click} class={classes!("secondary")}>{"Adjust"}</button>
+                                <button onclick={on_remove_click} class={classes!("red-bg-bd")}>{"Remove"}</button>
+                            </td>
+                        </tr>
+                        if *adjusted == Some(song.id) {
+                            <tr>
+                                <td colspan="4">
+                                    <SetlistSongOverridesForm
+                                        setlist_id={props.setlist_id}
+                                        song_id={song.id}
+                                        overrides={song.overrides.clone()}
+                                        on_update={on_overrides_update.clone()}
+                                        on_cancel={on_overrides_cancel.clone()}
+                                    />
+                                </td>
+                            </tr>
+                        }
+                        </>
+                    }
+                })
         }
         </tbody>
     </table>
+    </>
     }
 }
@@ -0,0 +1,132 @@
+use setlistrs_types::{GigDetails, SetlistPatch, SetlistSummary};
+use web_sys::{FormData, MouseEvent, SubmitEvent};
+use yew::{function_component, html, use_state, Callback, Html, Properties, TargetCast};
+
+use crate::api;
+use crate::components::use_notifier;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct SetlistGigDetailsProps {
+    pub setlist_id: i64,
+    pub summary: SetlistSummary,
+    pub on_update: Callback<SetlistSummary>,
+}
+
+#[function_component(SetlistGigDetails)]
+pub fn setlist_gig_details(
+    SetlistGigDetailsProps {
+        setlist_id,
+        summary,
+        on_update,
+    }: &SetlistGigDetailsProps,
+) -> Html {
+    let editing = use_state(|| false);
+    let notifier = use_notifier();
+
+    let on_edit_click = {
+        let editing = editing.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            editing.set(!*editing);
+        })
+    };
+
+    let onsubmit = {
+        let editing = editing.clone();
+        let on_update = on_update.clone();
+        let setlist_id = *setlist_id;
+        let notifier = notifier.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let form_data = FormData::new_with_form(&e.target_unchecked_into())
+                .expect("This is going to work since we are sure form exists.");
+
+            // Empty fields are sent as empty strings, which clears them on server side.
+            let setlist_patch = SetlistPatch {
+                display_title: form_data.get("display_title").as_string(),
+                gig: GigDetails {
+                    gig_date: form_data.get("gig_date").as_string(),
+                    start_time: form_data.get("start_time").as_string(),
+                    venue: form_data.get("venue").as_string(),
+                    notes: form_data.get("notes").as_string(),
+                },
+            };
+
+            let editing = editing.clone();
+            let on_update = on_update.clone();
+            let notifier = notifier.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = api::client()
+                    .update_setlist(setlist_id, &setlist_patch)
+                    .await;
+                if let Some(setlist) = notifier.ok(result) {
+                    on_update.emit(SetlistSummary {
+                        display_title: setlist.display_title,
+                        gig: setlist.gig,
+                    });
+                    editing.set(false);
+                }
+            });
+        })
+    };
+
+    let gig = &summary.gig;
+
+    if *editing {
+        return html! {
+            <article>
+                <form {onsubmit}>
+                    <label for="display_title">{"Setlist name"}</label>
+                    <input type="text" name="display_title" id="display_title" value={summary.display_title.clone()}/>
+                    <div class="grid">
+                        <label for="gig_date">
+                            {"Gig date"}
+                            <input type="date" name="gig_date" id="gig_date" value={gig.gig_date.clone()}/>
+                        </label>
+                        <label for="start_time">
+                            {"Start time"}
+                            <input type="time" name="start_time" id="start_time" value={gig.start_time.clone()}/>
+                        </label>
+                    </div>
+                    <label for="venue">{"Venue"}</label>
+                    <input type="text" name="venue" id="venue" value={gig.venue.clone()}/>
+                    <label for="notes">{"Notes"}</label>
+                    <textarea name="notes" id="notes" value={gig.notes.clone().unwrap_or_default()}></textarea>
+                    <div class="grid">
+                        <button type="submit">{"Save details"}</button>
+                        <button class="secondary" onclick={on_edit_click}>{"Cancel"}</button>
+                    </div>
+                </form>
+            </article>
+        };
+    }
+
+    let when = [gig.gig_date.clone(), gig.start_time.clone()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    html! {
+        <article>
+            <header>
+                <h2>{ &summary.display_title }</h2>
+            </header>
+            <p>
+                { if when.is_empty() { "No date set".to_string() } else { when } }
+                { " · " }
+                { gig.venue.clone().unwrap_or_else(|| "No venue set".to_string()) }
+            </p>
+            {
+                match &gig.notes {
+                    Some(notes) => html! { <p>{ notes }</p> },
+                    None => html! {},
+                }
+            }
+            <footer>
+                <button onclick={on_edit_click}>{"Edit details"}</button>
+            </footer>
+        </article>
+    }
+}
@@ -1,60 +1,93 @@
-use gloo_net::http::Request;
-
-use setlistrs_types::SetlistList as SetlistListModel;
-use web_sys::{HtmlElement, MouseEvent};
+use setlistrs_types::error::ErrorCode;
+use web_sys::{Event, HtmlElement, HtmlInputElement, MouseEvent};
 use yew::{
     classes, function_component, html, use_effect_with_deps, use_reducer, use_state, AttrValue,
     Callback, Html, Properties, Reducible, TargetCast,
 };
 use yew_router::prelude::use_navigator;
 
+use crate::api;
 use crate::app::Route;
+use crate::components::use_notifier;
 
 #[function_component(SetlistList)]
 pub fn setlist_list() -> Html {
     let setlists = use_state(|| None);
+    let archived = use_state(|| false);
+    let navigator = use_navigator().expect("There is no reason it will not work.");
+    let notifier = use_notifier();
 
     {
         let setlists = setlists.clone();
         use_effect_with_deps(
-            move |_| {
+            move |archived| {
                 let setlists = setlists.clone();
+                let navigator = navigator.clone();
+                let notifier = notifier.clone();
+                let archived = *archived;
 
                 wasm_bindgen_futures::spawn_local(async move {
-                    let response = Request::get("http://127.0.0.1:8081/setlists").send().await;
-                    match response {
-                        Ok(response) => {
-                            let possible_setlists: Result<SetlistListModel, _> =
-                                response.json().await;
-                            match possible_setlists {
-                                Ok(s) => setlists.set(Some(s)),
-                                Err(_) => panic!(), // TODO -> handle it gracefuly
+                    let client = api::client();
+                    let result = match archived {
+                        true => client.list_archived_setlists().await,
+                        false => client.list_setlists().await,
+                    };
+                    match result {
+                        Err(e) if e.has_code(ErrorCode::Unauthorized) => {
+                            navigator.push(&Route::UserLogin)
+                        }
+                        result => {
+                            if let Some(s) = notifier.ok(result) {
+                                setlists.set(Some(s))
                             }
                         }
-                        Err(_) => panic!(), // TODO -> handle it gracefuly
                     };
                 });
 
                 || ()
             },
-            (),
+            *archived,
         );
     }
+
+    let on_archived_change = {
+        let setlists = setlists.clone();
+        let archived = archived.clone();
+        Callback::from(move |e: Event| {
+            let checkbox: HtmlInputElement = e.target_unchecked_into();
+            // Content is fetched again, its list is not reused.
+            setlists.set(None);
+            archived.set(checkbox.checked());
+        })
+    };
+
     html! {
         <article>
+        <label for="archived">
+            <input
+                type="checkbox"
+                role="switch"
+                id="archived"
+                checked={*archived}
+                onchange={on_archived_change}
+            />
+            {"Archived gigs"}
+        </label>
         {
             match &*setlists {
                 Some(setlists) => {
                     let actual_setlist: Vec<SingleSetlist> = setlists
                         .data
                         .iter()
-                        .map(|( id, display_title )| SingleSetlist {
-                            id: id.clone(),
-                            display_title: AttrValue::from(display_title.clone())
+                        .map(|( id, summary )| SingleSetlist {
+                            id: *id,
+                            display_title: AttrValue::from(summary.display_title.clone()),
+                            gig_date: summary.gig.gig_date.clone().map(AttrValue::from),
+                            venue: summary.gig.venue.clone().map(AttrValue::from),
                         })
                     .collect();
 
-                    html! { <SetlistListContent setlists={actual_setlist} /> }
+                    html! { <SetlistListContent setlists={actual_setlist} archived={*archived} /> }
                 },
                 None => html! { <p> { "Fetching data..." } </p> }
             }
@@ -63,37 +96,38 @@ pub fn setlist_list() -> Html {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 struct SingleSetlist {
     pub id: i64,
     pub display_title: AttrValue,
+    pub gig_date: Option<AttrValue>,
+    pub venue: Option<AttrValue>,
 }
 
 #[derive(Properties, PartialEq)]
 struct SetlistListContentProps {
     pub setlists: Vec<SingleSetlist>,
+    pub archived: bool,
 }
 
-enum SetlistDeleteAction {
-    Delete(i64),
+enum SetlistListAction {
+    /// Setlist was deleted, archived or moved back from archive.
+    Remove(i64),
 }
 struct Setlists {
     pub setlists: Vec<SingleSetlist>,
 }
 impl Reducible for Setlists {
-    type Action = SetlistDeleteAction;
+    type Action = SetlistListAction;
 
     fn reduce(self: std::rc::Rc<Self>, action: Self::Action) -> std::rc::Rc<Self> {
         match action {
-            SetlistDeleteAction::Delete(setlist_id) => {
+            SetlistListAction::Remove(setlist_id) => {
                 let list = self
                     .setlists
                     .iter()
                     .filter(|single_setlist| single_setlist.id != setlist_id)
-                    .map(|single_setlist| SingleSetlist {
-                        id: single_setlist.id,
-                        display_title: single_setlist.display_title.clone(),
-                    })
+                    .cloned()
                     .collect();
 
                 Setlists { setlists: list }.into()
@@ -103,16 +137,13 @@ impl Reducible for Setlists {
 }
 
 #[function_component(SetlistListContent)]
-fn setlist_list_content(SetlistListContentProps { setlists }: &SetlistListContentProps) -> Html {
-    let lorem: Vec<SingleSetlist> = setlists
-        .into_iter()
-        .map(|single_setlist| SingleSetlist {
-            id: single_setlist.id,
-            display_title: single_setlist.display_title.clone(),
-        })
-        .collect();
+fn setlist_list_content(
+    SetlistListContentProps { setlists, archived }: &SetlistListContentProps,
+) -> Html {
+    let lorem: Vec<SingleSetlist> = setlists.to_vec();
 
     let owned_setlists = use_reducer(|| Setlists { setlists: lorem });
+    let notifier = use_notifier();
     let on_details_click = {
         let navigator = use_navigator().expect("There is no reason this would not work");
         Callback::from(move |e: MouseEvent| {
@@ -134,6 +165,39 @@ fn setlist_list_content(SetlistListContentProps { setlists }: &SetlistListConten
 
     // let deleted: UseStateHandle<Option<String>> = use_state(|| None);
 
+    let on_archive_click = {
+        let owned_setlists = owned_setlists.clone();
+        let notifier = notifier.clone();
+        let archived = *archived;
+        let message = match archived {
+            true => "Setlist moved back to active ones",
+            false => "Setlist archived",
+        };
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            let button: HtmlElement = e.target_unchecked_into();
+
+            let setlist_id = button
+                .get_attribute("data-setlist-id")
+                .map(|setlist_id| setlist_id.parse::<i64>());
+            if let Some(Ok(setlist_id)) = setlist_id {
+                let owned_setlists = owned_setlists.clone();
+                let notifier = notifier.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let client = api::client();
+                    let result = match archived {
+                        true => client.unarchive_setlist(setlist_id).await,
+                        false => client.archive_setlist(setlist_id).await,
+                    };
+                    if notifier.ok(result).is_some() {
+                        owned_setlists.dispatch(SetlistListAction::Remove(setlist_id));
+                        notifier.success(message);
+                    }
+                });
+            }
+        })
+    };
+
     let on_delete_click = {
         let owned_setlists = owned_setlists.clone();
         // let deleted = deleted.clone();
@@ -144,22 +208,14 @@ fn setlist_list_content(SetlistListContentProps { setlists }: &SetlistListConten
             match button.get_attribute("data-setlist-id") {
                 Some(setlist_id) => {
                     let owned_setlists = owned_setlists.clone();
+                    let notifier = notifier.clone();
                     wasm_bindgen_futures::spawn_local(async move {
-                        let response = Request::delete(
-                            format!("http://127.0.0.1:8081/setlists/{}", setlist_id).as_str(),
-                        )
-                        .send()
-                        .await;
-                        match response {
-                            Ok(response) => {
-                                if response.status() == 204 {
-                                    owned_setlists.dispatch(SetlistDeleteAction::Delete(
-                                        setlist_id.parse::<i64>().expect(""),
-                                    ));
-                                }
-                            }
-                            Err(_) => panic!(), // TODO -> handle it gracefuly
-                        };
+                        let setlist_id = setlist_id.parse::<i64>().expect("");
+                        let result = api::client().delete_setlist(setlist_id).await;
+                        if notifier.ok(result).is_some() {
+                            owned_setlists.dispatch(SetlistListAction::Remove(setlist_id));
+                            notifier.success("Setlist deleted");
+                        }
                     });
                 }
                 None => panic!(),
@@ -169,9 +225,11 @@ fn setlist_list_content(SetlistListContentProps { setlists }: &SetlistListConten
 
     html! {
         <table>
-            <caption>{"Setlists"}</caption>
+            <caption>{ if *archived { "Archived setlists" } else { "Setlists" } }</caption>
             <thead class={classes!("grid")}>
                 <th>{"Display title"}</th>
+                <th>{"Gig date"}</th>
+                <th>{"Venue"}</th>
                 <th>{"Action"}</th>
             </thead>
             <tbody>
@@ -179,8 +237,11 @@ fn setlist_list_content(SetlistListContentProps { setlists }: &SetlistListConten
             for owned_setlists.setlists.iter().map(|single_setlist| html! {
                 <tr class={classes!("grid")}>
                     <td class={classes!("grid")}>{&single_setlist.display_title}</td>
+                    <td class={classes!("grid")}>{single_setlist.gig_date.clone().unwrap_or_default()}</td>
+                    <td class={classes!("grid")}>{single_setlist.venue.clone().unwrap_or_default()}</td>
                     <td class={classes!("grid")}>
                     <button onclick={on_details_click.clone()} data-setlist-id={single_setlist.id.to_string()}>{"Details / Edit"}</button>
+                    <button onclick={on_archive_click.clone()} class={classes!("secondary")} data-setlist-id={single_setlist.id.to_string()}>{ if *archived { "Unarchive" } else { "Archive" } }</button>
                     <button onclick={on_delete_click.clone()} class={classes!("red-bg-bd")} data-setlist-id={single_setlist.id.to_string()}>{"Delete"}</button>
                     </td>
                 </tr>
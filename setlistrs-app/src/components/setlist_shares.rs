@@ -0,0 +1,149 @@
+use setlistrs_types::error::ErrorCode;
+use setlistrs_types::validation::MAX_SHARE_EXPIRY_DAYS;
+use setlistrs_types::{NewSetlistShare, SetlistShare};
+use web_sys::{FormData, MouseEvent, SubmitEvent};
+use yew::{
+    classes, function_component, html, use_effect_with_deps, use_state, Callback, Html, Properties,
+    TargetCast,
+};
+
+use crate::api;
+use crate::components::use_notifier;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct SetlistSharesProps {
+    pub setlist_id: i64,
+}
+
+/// Read-only links to the setlist for people without account, e.g. sound
+/// engineer or a dep musician.
+#[function_component(SetlistShares)]
+pub fn setlist_shares(SetlistSharesProps { setlist_id }: &SetlistSharesProps) -> Html {
+    let shares = use_state(Vec::<SetlistShare>::new);
+    let setlist_id = *setlist_id;
+    let notifier = use_notifier();
+
+    {
+        let shares = shares.clone();
+        let notifier = notifier.clone();
+        use_effect_with_deps(
+            move |_| {
+                let shares = shares.clone();
+                let notifier = notifier.clone();
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api::client().list_setlist_shares(setlist_id).await {
+                        // Viewers of a band setlist can not manage its links.
+                        Err(e) if e.has_code(ErrorCode::Forbidden) => {}
+                        result => {
+                            if let Some(s) = notifier.ok(result) {
+                                shares.set(s.data)
+                            }
+                        }
+                    };
+                });
+
+                || ()
+            },
+            (),
+        );
+    }
+
+    let onsubmit = {
+        let shares = shares.clone();
+        let notifier = notifier.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let form_data = FormData::new_with_form(&e.target_unchecked_into())
+                .expect("This is going to work since we are sure form exists.");
+
+            let new_share = NewSetlistShare {
+                expires_in_days: form_data
+                    .get("expires_in_days")
+                    .as_string()
+                    .and_then(|days| days.parse().ok()),
+            };
+
+            let shares = shares.clone();
+            let notifier = notifier.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = api::client().share_setlist(setlist_id, &new_share).await;
+                if let Some(share) = notifier.ok(result) {
+                    let mut current_shares = (*shares).clone();
+                    current_shares.push(share);
+                    shares.set(current_shares);
+                    notifier.success("Share link created");
+                }
+            });
+        })
+    };
+
+    html! {
+        <details>
+            <summary>{"Share read-only link"}</summary>
+            <form {onsubmit}>
+                <div class="grid">
+                    <label for="expires_in_days">
+                        {"Expires in days"}
+                        <input type="number" min="1" max={MAX_SHARE_EXPIRY_DAYS.to_string()} name="expires_in_days" id="expires_in_days" placeholder="Never"/>
+                    </label>
+                    <button type="submit">{"Create link"}</button>
+                </div>
+            </form>
+            <ul>
+            {
+                for shares.iter().map(|share| {
+                    let on_revoke_click = {
+                        let shares = shares.clone();
+                        let token = share.token.clone();
+                        let notifier = notifier.clone();
+                        Callback::from(move |e: MouseEvent| {
+                            e.prevent_default();
+                            let shares = shares.clone();
+                            let token = token.clone();
+                            let notifier = notifier.clone();
+                            wasm_bindgen_futures::spawn_local(async move {
+                                let result = api::client().revoke_setlist_share(setlist_id, &token).await;
+                                if notifier.ok(result).is_some() {
+                                    let mut remaining_shares = (*shares).clone();
+                                    remaining_shares.retain(|share| share.token != token);
+                                    shares.set(remaining_shares);
+                                }
+                            });
+                        })
+                    };
+                    let link = share_link(&share.token);
+
+                    html! {
+                        <li>
+                            <a href={link.clone()} target="_blank">{ link }</a>
+                            <small>{ " · " }{ expiry(share.expires_at) }{ " " }</small>
+                            <a href="#" class={classes!("secondary")} onclick={on_revoke_click}>{"Revoke"}</a>
+                        </li>
+                    }
+                })
+            }
+            </ul>
+        </details>
+    }
+}
+
+fn share_link(token: &str) -> String {
+    let origin = web_sys::window()
+        .and_then(|window| window.location().origin().ok())
+        .unwrap_or_default();
+
+    format!("{}/shared/setlists/{}", origin, token)
+}
+
+fn expiry(expires_at: Option<i64>) -> String {
+    match expires_at {
+        Some(expires_at) => {
+            let date = js_sys::Date::new(&(expires_at as f64 * 1000.0).into());
+            let date: String = date.to_iso_string().into();
+            format!("expires {}", &date[..10])
+        }
+        None => "never expires".to_string(),
+    }
+}
@@ -0,0 +1,126 @@
+use setlistrs_types::{Setlist, SetlistSongOverrides, SetlistSongOverridesPatch};
+use web_sys::{FormData, MouseEvent, SubmitEvent};
+use yew::{function_component, html, Callback, Html, Properties, TargetCast};
+
+use crate::api;
+use crate::components::song_add::CHORDPRO_PLACEHOLDER;
+use crate::components::use_notifier;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct SetlistSongOverridesFormProps {
+    pub setlist_id: i64,
+    pub song_id: i64,
+    pub overrides: SetlistSongOverrides,
+    pub on_update: Callback<Setlist>,
+    pub on_cancel: Callback<()>,
+}
+
+/// Form changing how song is played in one setlist, the song itself is not changed.
+#[function_component(SetlistSongOverridesForm)]
+pub fn setlist_song_overrides_form(
+    SetlistSongOverridesFormProps {
+        setlist_id,
+        song_id,
+        overrides,
+        on_update,
+        on_cancel,
+    }: &SetlistSongOverridesFormProps,
+) -> Html {
+    let notifier = use_notifier();
+    let on_cancel_click = {
+        let on_cancel = on_cancel.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            on_cancel.emit(());
+        })
+    };
+
+    let onsubmit = {
+        let on_update = on_update.clone();
+        let setlist_id = *setlist_id;
+        let song_id = *song_id;
+        let notifier = notifier.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let form_data = FormData::new_with_form(&e.target_unchecked_into())
+                .expect("This is going to work since we are sure form exists.");
+
+            // Empty fields are sent as empty strings and capo as 0, which clears them.
+            let overrides_patch = SetlistSongOverridesPatch {
+                chords: form_data.get("custom_chords").as_string(),
+                chordpro: form_data.get("custom_chordpro").as_string(),
+                transpose: form_data
+                    .get("transpose")
+                    .as_string()
+                    .and_then(|transpose| transpose.parse().ok()),
+                capo: form_data
+                    .get("capo")
+                    .as_string()
+                    .map(|capo| capo.parse().unwrap_or_default()),
+                note: form_data.get("note").as_string(),
+            };
+
+            let on_update = on_update.clone();
+            let notifier = notifier.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = api::client()
+                    .update_setlist_song(setlist_id, song_id, &overrides_patch)
+                    .await;
+                if let Some(setlist) = notifier.ok(result) {
+                    on_update.emit(setlist);
+                }
+            });
+        })
+    };
+
+    html! {
+        <form {onsubmit}>
+            <div class="grid">
+                <label for="transpose">
+                    {"Transpose (semitones)"}
+                    <input
+                        type="number"
+                        name="transpose"
+                        id="transpose"
+                        min="-11"
+                        max="11"
+                        value={overrides.transpose.to_string()}
+                    />
+                </label>
+                <label for="capo">
+                    {"Capo"}
+                    <input
+                        type="number"
+                        name="capo"
+                        id="capo"
+                        min="0"
+                        value={overrides.capo.map(|capo| capo.to_string())}
+                    />
+                </label>
+            </div>
+            <label for="note">{"Note"}</label>
+            <input type="text" name="note" id="note" placeholder={"ex: acoustic, slower"} value={overrides.note.clone()}/>
+            <label for="custom_chords">{"Chords for this setlist"}</label>
+            <input
+                type="text"
+                name="custom_chords"
+                id="custom_chords"
+                placeholder={"leave empty to use song chords"}
+                value={overrides.chords.clone()}
+            />
+            <label for="custom_chordpro">{"ChordPro for this setlist"}</label>
+            <textarea
+                name="custom_chordpro"
+                id="custom_chordpro"
+                rows="8"
+                placeholder={CHORDPRO_PLACEHOLDER}
+                value={overrides.chordpro.clone().unwrap_or_default()}
+            ></textarea>
+            <div class="grid">
+                <button type="submit">{"Save for this setlist"}</button>
+                <button class="secondary" onclick={on_cancel_click}>{"Cancel"}</button>
+            </div>
+        </form>
+    }
+}
@@ -0,0 +1,101 @@
+use setlistrs_types::Song;
+use web_sys::HtmlInputElement;
+use yew::{
+    function_component, html, use_effect_with_deps, use_state, Callback, Html, InputEvent,
+    MouseEvent, Properties, TargetCast,
+};
+
+use crate::api;
+use crate::components::use_notifier;
+
+const MAX_MATCHES: usize = 10;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct SetlistSongPickerProps {
+    pub excluded: Vec<i64>,
+    pub on_pick: Callback<i64>,
+}
+
+#[function_component(SetlistSongPicker)]
+pub fn setlist_song_picker(
+    SetlistSongPickerProps { excluded, on_pick }: &SetlistSongPickerProps,
+) -> Html {
+    let songs = use_state(Vec::<(i64, Song)>::new);
+    let search = use_state(String::new);
+    let notifier = use_notifier();
+
+    {
+        let songs = songs.clone();
+        use_effect_with_deps(
+            move |_| {
+                let songs = songs.clone();
+                let notifier = notifier.clone();
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Some(s) = notifier.ok(api::client().list_songs().await) {
+                        songs.set(s.data);
+                    }
+                });
+
+                || ()
+            },
+            (),
+        );
+    }
+
+    let oninput = {
+        let search = search.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            search.set(input.value());
+        })
+    };
+
+    let phrase = search.trim().to_lowercase();
+    let matches: Vec<&(i64, Song)> = if phrase.is_empty() {
+        Vec::new()
+    } else {
+        songs
+            .iter()
+            .filter(|(song_id, song)| {
+                !excluded.contains(song_id) && song.name.to_lowercase().contains(&phrase)
+            })
+            .take(MAX_MATCHES)
+            .collect()
+    };
+
+    html! {
+        <fieldset>
+            <label for="song_search">{"Add song"}</label>
+            <input
+                type="search"
+                id="song_search"
+                placeholder={"Start typing song title"}
+                value={(*search).clone()}
+                {oninput}
+            />
+            <ul>
+            {
+                for matches.into_iter().map(|(song_id, song)| {
+                    let onclick = {
+                        let on_pick = on_pick.clone();
+                        let search = search.clone();
+                        let song_id = *song_id;
+                        Callback::from(move |e: MouseEvent| {
+                            e.prevent_default();
+                            search.set(String::new());
+                            on_pick.emit(song_id);
+                        })
+                    };
+
+                    html! {
+                        <li>
+                            <a href="#" {onclick}>{ &song.name }</a>
+                        </li>
+                    }
+                })
+            }
+            </ul>
+        </fieldset>
+    }
+}
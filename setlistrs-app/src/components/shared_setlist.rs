@@ -0,0 +1,139 @@
+use setlistrs_types::error::ErrorCode;
+use yew::{function_component, html, use_effect_with_deps, use_state, AttrValue, Html, Properties};
+
+use crate::api;
+use crate::components::{use_notifier, ChordProView};
+
+#[derive(Debug, Clone, Eq, PartialEq, Properties)]
+pub struct SharedSetlistProps {
+    pub token: AttrValue,
+}
+
+/// Setlist opened from a share link, it is read-only and needs no login.
+#[function_component(SharedSetlist)]
+pub fn shared_setlist(SharedSetlistProps { token }: &SharedSetlistProps) -> Html {
+    let setlist = use_state(|| None);
+    let not_found = use_state(|| false);
+    let notifier = use_notifier();
+
+    {
+        let setlist = setlist.clone();
+        let not_found = not_found.clone();
+        let token = token.clone();
+        use_effect_with_deps(
+            move |_| {
+                let setlist = setlist.clone();
+                let notifier = notifier.clone();
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api::client().get_shared_setlist(&token).await {
+                        Err(e) if e.has_code(ErrorCode::NotFound) => not_found.set(true),
+                        result => {
+                            if let Some(s) = notifier.ok(result) {
+                                setlist.set(Some(s))
+                            }
+                        }
+                    };
+                });
+
+                || ()
+            },
+            (),
+        );
+    }
+
+    if *not_found {
+        return html! { <p>{ "This link was revoked or has expired." }</p> };
+    }
+
+    let setlist = match &*setlist {
+        Some(setlist) => setlist,
+        None => return html! { <p> { "Fetching data..." } </p> },
+    };
+    let gig = &setlist.gig;
+    let when = [gig.gig_date.clone(), gig.start_time.clone()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    html! {
+        <>
+        <article>
+            <header>
+                <h2>{ &setlist.display_title }</h2>
+            </header>
+            <p>
+                { if when.is_empty() { "No date set".to_string() } else { when } }
+                { " · " }
+                { gig.venue.clone().unwrap_or_else(|| "No venue set".to_string()) }
+            </p>
+            {
+                match &gig.notes {
+                    Some(notes) => html! { <p>{ notes }</p> },
+                    None => html! {},
+                }
+            }
+            <footer class="grid">
+                <a
+                    role="button"
+                    target="_blank"
+                    href={api::url(&format!("/shared/setlists/{}/export.pdf", token))}
+                >{"Stage sheet PDF"}</a>
+                <a
+                    role="button"
+                    class="secondary"
+                    target="_blank"
+                    href={api::url(&format!("/shared/setlists/{}/export.pdf?chords=true", token))}
+                >{"PDF with chords"}</a>
+            </footer>
+        </article>
+        <table>
+            <thead>
+            <th>{"#"}</th>
+            <th>{"Song"}</th>
+            <th>{"Chords"}</th>
+            </thead>
+            <tbody>
+            {
+                for setlist.songs.iter().enumerate().map(|(index, (_, song))| {
+                    let adjustments: Vec<String> = [
+                        song.overrides.capo.map(|capo| format!("Capo {}", capo)),
+                        song.overrides.note.clone(),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+
+                    html! {
+                        <tr>
+                            <td>{ index + 1 }</td>
+                            <td>
+                                { &song.display_title }
+                                if !adjustments.is_empty() {
+                                    <br/>
+                                    <small>{ adjustments.join(" · ") }</small>
+                                }
+                            </td>
+                            <td>
+                            {
+                                match &song.chordpro {
+                                    Some(chordpro) => html! {
+                                        <details>
+                                            <summary>{ &song.chords }</summary>
+                                            <ChordProView body={AttrValue::from(chordpro.clone())} show_header={false} />
+                                        </details>
+                                    },
+                                    None => html! { &song.chords },
+                                }
+                            }
+                            </td>
+                        </tr>
+                    }
+                })
+            }
+            </tbody>
+        </table>
+        </>
+    }
+}
@@ -1,30 +1,45 @@
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 
-use gloo_net::http::Request;
+use setlistrs_client::Error;
+use setlistrs_types::error::{ErrorCode, FieldError};
+use setlistrs_types::validation::{is_http_url, is_youtube_url, validate_song};
 use setlistrs_types::{Song, YTLink};
-use web_sys::{Element, FormData};
+use web_sys::{Element, FormData, HtmlFormElement};
 use yew::prelude::*;
 use yew_router::prelude::use_navigator;
 
+use crate::api;
 use crate::app::Route;
+use crate::components::{use_notifier, Notifier};
+
+pub(crate) const CHORDPRO_PLACEHOLDER: &str =
+    "lyrics with chords in ChordPro format, ex:\n{title: Song}\n{start_of_chorus}\n[G]Sing a[D]long\n{end_of_chorus}";
 
 #[derive(Clone, PartialEq, Properties)]
-struct LinkWithDisplayTitleProps {
+pub(crate) struct LinkWithDisplayTitleProps {
     pub input_name_prefix: String,
     pub entries_list: LinkWithDisplayTitleIdList,
     pub on_add: Callback<()>,
     pub on_remove: Callback<i32>,
     pub min_entries_count: Option<i32>,
+    #[prop_or_default]
+    pub initial_values: HashMap<i32, YTLink>,
+    /// Error messages keyed by name of the input they belong to.
+    #[prop_or_default]
+    pub errors: HashMap<String, String>,
 }
 
 #[function_component(LinkWithDisplayTitle)]
-fn link_with_display_title(
+pub(crate) fn link_with_display_title(
     LinkWithDisplayTitleProps {
         input_name_prefix,
         entries_list,
         on_add,
         on_remove,
         min_entries_count,
+        initial_values,
+        errors,
     }: &LinkWithDisplayTitleProps,
 ) -> Html {
     let on_plus_click = {
@@ -60,10 +75,25 @@ fn link_with_display_title(
         <>
 
         {
-            for entries_list.list.iter().map(|entry_id| html! {
+            for entries_list.list.iter().map(|entry_id| {
+                let display_title_name = format!("{}_display_title_{}", input_name_prefix, entry_id);
+                let url_name = format!("{}_url_{}", input_name_prefix, entry_id);
+
+                html! {
+                <>
                 <div class={classes!("grid")}>
-                    <input name={ format!("{}_display_title_{}", input_name_prefix, entry_id) } placeholder={ "Title" } />
-                    <input name={ format!("{}_url_{}", input_name_prefix, entry_id) } placeholder={ "Url" } />
+                    <input
+                        name={ display_title_name.clone() }
+                        placeholder={ "Title" }
+                        value={ initial_values.get(entry_id).and_then(|yt_link| yt_link.display_title.clone()) }
+                        aria-invalid={ errors.get(&display_title_name).map(|_| "true") }
+                    />
+                    <input
+                        name={ url_name.clone() }
+                        placeholder={ "Url" }
+                        value={ initial_values.get(entry_id).map(|yt_link| yt_link.url.clone()) }
+                        aria-invalid={ errors.get(&url_name).map(|_| "true") }
+                    />
                     {
                         if display_minus_button {
                             html! {
@@ -72,6 +102,10 @@ fn link_with_display_title(
                         } else { html!{""} }
                     }
                 </div>
+                { error_hint(errors, &display_title_name) }
+                { error_hint(errors, &url_name) }
+                </>
+                }
             })
         }
 
@@ -80,17 +114,49 @@ fn link_with_display_title(
     }
 }
 
-enum LinkWithDisplayTitleAction {
+pub(crate) enum LinkWithDisplayTitleAction {
     Add(),
     Remove(i32),
 }
 
 #[derive(Clone, PartialEq)]
-struct LinkWithDisplayTitleIdList {
+pub(crate) struct LinkWithDisplayTitleIdList {
     pub list: Vec<i32>,
     pub last_added: i32,
 }
 
+impl LinkWithDisplayTitleIdList {
+    /// Entry ids for already persisted links, paired with the links themselves,
+    /// so they can be passed as `initial_values` of `LinkWithDisplayTitle`.
+    pub fn for_links(yt_links: &[YTLink]) -> (Self, HashMap<i32, YTLink>) {
+        let list: Vec<i32> = (1..=yt_links.len() as i32).collect();
+        let initial_values = list.iter().copied().zip(yt_links.iter().cloned()).collect();
+
+        (
+            Self {
+                last_added: list.last().copied().unwrap_or(0),
+                list,
+            },
+            initial_values,
+        )
+    }
+
+    pub fn links_from(&self, form_data: &FormData, input_name_prefix: &str) -> Vec<YTLink> {
+        self.list
+            .iter()
+            .map(|link_id| YTLink {
+                url: form_data
+                    .get(&format!("{}_url_{}", input_name_prefix, link_id))
+                    .as_string()
+                    .unwrap_or_default(),
+                display_title: form_data
+                    .get(&format!("{}_display_title_{}", input_name_prefix, link_id))
+                    .as_string(),
+            })
+            .collect()
+    }
+}
+
 impl Reducible for LinkWithDisplayTitleIdList {
     type Action = LinkWithDisplayTitleAction;
 
@@ -120,6 +186,135 @@ impl Reducible for LinkWithDisplayTitleIdList {
     }
 }
 
+/// Song as filled in the form, together with entry ids of its links so
+/// errors of single links can be shown next to their inputs.
+pub(crate) struct SongForm {
+    pub song: Song,
+    source_ids: Vec<i32>,
+    cover_ids: Vec<i32>,
+}
+
+impl SongForm {
+    pub fn read(
+        form_data: &FormData,
+        yt_links: &LinkWithDisplayTitleIdList,
+        cover_links: &LinkWithDisplayTitleIdList,
+    ) -> Self {
+        let (source_ids, source) = filled_links(form_data, yt_links, "yt");
+        let (cover_ids, cover) = filled_links(form_data, cover_links, "cover");
+
+        Self {
+            song: Song {
+                name: form_data.get("song_title").as_string().unwrap_or_default(),
+                source,
+                cover: Some(cover),
+                chords: form_data.get("chords").as_string().unwrap_or_default(),
+                chordpro: form_data
+                    .get("chordpro")
+                    .as_string()
+                    .filter(|chordpro| !chordpro.is_empty()),
+            },
+            source_ids,
+            cover_ids,
+        }
+    }
+
+    /// Same checks server does, plus song has to have at least one source
+    /// and sources have to be YouTube links. Server accepts any http(s) link,
+    /// so other API clients are not limited by this.
+    pub fn validate(&self) -> HashMap<String, String> {
+        let mut field_errors = validate_song(&self.song);
+        if self.song.source.is_empty() {
+            field_errors.push(FieldError::new("source", "At least one source is required"));
+        }
+        for (index, yt_link) in self.song.source.iter().enumerate() {
+            if is_http_url(&yt_link.url) && !is_youtube_url(&yt_link.url) {
+                field_errors.push(FieldError::new(
+                    format!("source[{}].url", index),
+                    format!("{} is not a YouTube link", yt_link.url),
+                ));
+            }
+        }
+
+        self.input_errors(field_errors)
+    }
+
+    /// Field errors keyed by name of the input they belong to, e.g.
+    /// `source[0].url` belongs to `yt_url_<id of first source entry>`.
+    fn input_errors(&self, field_errors: Vec<FieldError>) -> HashMap<String, String> {
+        field_errors
+            .into_iter()
+            .map(|field_error| (self.input_name(&field_error.field), field_error.message))
+            .collect()
+    }
+
+    /// Field errors the server responded with are shown next to inputs,
+    /// other errors as notification. Gives `None` when song was not saved.
+    pub fn saved<T>(
+        &self,
+        result: Result<T, Error>,
+        errors: &UseStateHandle<HashMap<String, String>>,
+        notifier: &Notifier,
+    ) -> Option<T> {
+        match result {
+            Err(Error::Api(api_error)) if api_error.code == ErrorCode::Validation => {
+                errors.set(self.input_errors(api_error.fields));
+                None
+            }
+            result => notifier.ok(result),
+        }
+    }
+
+    fn input_name(&self, field: &str) -> String {
+        if field == "name" {
+            return "song_title".to_string();
+        }
+        for (field_prefix, input_prefix, ids) in [
+            ("source[", "yt", &self.source_ids),
+            ("cover[", "cover", &self.cover_ids),
+        ] {
+            let link_field = field
+                .strip_prefix(field_prefix)
+                .and_then(|rest| rest.split_once("]."));
+            if let Some((index, link_field)) = link_field {
+                if let Some(id) = index.parse::<usize>().ok().and_then(|index| ids.get(index)) {
+                    return format!("{}_{}_{}", input_prefix, link_field, id);
+                }
+            }
+        }
+
+        field.to_string()
+    }
+}
+
+/// Entries left completely empty are not sent.
+fn filled_links(
+    form_data: &FormData,
+    links: &LinkWithDisplayTitleIdList,
+    input_name_prefix: &str,
+) -> (Vec<i32>, Vec<YTLink>) {
+    links
+        .list
+        .iter()
+        .copied()
+        .zip(links.links_from(form_data, input_name_prefix))
+        .filter(|(_, yt_link)| {
+            !yt_link.url.trim().is_empty()
+                || yt_link
+                    .display_title
+                    .as_deref()
+                    .is_some_and(|display_title| !display_title.trim().is_empty())
+        })
+        .unzip()
+}
+
+pub(crate) fn error_hint(errors: &HashMap<String, String>, input_name: &str) -> Html {
+    match errors.get(input_name) {
+        Some(message) => html! { <small class={classes!("field-error")}>{ message }</small> },
+        None => html! {},
+    }
+}
+
 #[function_component(SongAdd)]
 pub fn add_song_form() -> Html {
     let yt_links_state = use_reducer(|| LinkWithDisplayTitleIdList {
@@ -131,6 +326,11 @@ pub fn add_song_form() -> Html {
         list: Vec::from([1]),
     });
     let navigator = use_navigator().expect("There is no reason it will not work.");
+    let form_ref = use_node_ref();
+    let errors = use_state(HashMap::<String, String>::new);
+    let touched = use_mut_ref(HashSet::<String>::new);
+    let invalid = use_state(|| true);
+    let notifier = use_notifier();
 
     let on_yt_link_add = {
         let yt_links_state = yt_links_state.clone();
@@ -154,10 +354,69 @@ pub fn add_song_form() -> Html {
         })
     };
 
+    // Client side errors are shown only for inputs user already touched,
+    // errors returned by server are shown always.
+    let validate = {
+        let form_ref = form_ref.clone();
+        let yt_links_state = yt_links_state.clone();
+        let covers_links_state = covers_links_state.clone();
+        let errors = errors.clone();
+        let touched = touched.clone();
+        let invalid = invalid.clone();
+
+        Callback::from(move |_: ()| {
+            let form = match form_ref.cast::<HtmlFormElement>() {
+                Some(form) => form,
+                None => return,
+            };
+            let form_data =
+                FormData::new_with_form(&form).expect("This will work since form exists.");
+            let input_errors =
+                SongForm::read(&form_data, &yt_links_state, &covers_links_state).validate();
+            invalid.set(!input_errors.is_empty());
+
+            let touched = touched.borrow();
+            errors.set(
+                input_errors
+                    .into_iter()
+                    .filter(|(input_name, _)| {
+                        touched.contains(input_name)
+                            || (input_name == "source"
+                                && touched.iter().any(|touched| touched.starts_with("yt_")))
+                    })
+                    .collect(),
+            );
+        })
+    };
+
+    {
+        let validate = validate.clone();
+        use_effect_with_deps(
+            move |_| {
+                validate.emit(());
+                || ()
+            },
+            ((*yt_links_state).clone(), (*covers_links_state).clone()),
+        );
+    }
+
+    let oninput = {
+        let touched = touched.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: Element = e.target_unchecked_into();
+            if let Some(input_name) = input.get_attribute("name") {
+                touched.borrow_mut().insert(input_name);
+            }
+            validate.emit(());
+        })
+    };
+
     let onsubmit = {
         let yt_links_state = yt_links_state.clone();
         let covers_links_state = covers_links_state.clone();
         let navigator = navigator.clone();
+        let errors = errors.clone();
+        let invalid = invalid.clone();
 
         Callback::from(move |e: SubmitEvent| {
             let navigator = navigator.clone();
@@ -166,57 +425,39 @@ pub fn add_song_form() -> Html {
             let form_data: FormData = FormData::new_with_form(&e.target_unchecked_into())
                 .expect("This will work since we have only one form.");
 
-            let yt_links: Vec<YTLink> = yt_links_state
-                .deref()
-                .list
-                .iter()
-                .map(|link_id| YTLink {
-                    url: match form_data.get(&format!("yt_url_{}", link_id)).as_string() {
-                        Some(url) => url.into(),
-                        None => todo!(),
-                    },
-                    display_title: form_data
-                        .get(&format!("yt_display_title_{}", link_id))
-                        .as_string(),
-                })
-                .collect();
-
-            let cover_links: Vec<YTLink> = covers_links_state
-                .deref()
-                .list
-                .iter()
-                .map(|link_id| YTLink {
-                    url: match form_data.get(&format!("cover_url_{}", link_id)).as_string() {
-                        Some(url) => url.into(),
-                        None => todo!(),
-                    },
-                    display_title: form_data
-                        .get(&format!("cover_display_title_{}", link_id))
-                        .as_string(),
-                })
-                .collect();
+            let song_form = SongForm::read(
+                &form_data,
+                yt_links_state.deref(),
+                covers_links_state.deref(),
+            );
+            let input_errors = song_form.validate();
+            if !input_errors.is_empty() {
+                invalid.set(true);
+                errors.set(input_errors);
+                return;
+            }
 
+            let errors = errors.clone();
+            let notifier = notifier.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                let _response = Request::post("http://127.0.0.1:8081/songs")
-                    .json(&Song {
-                        name: form_data.get("song_title").as_string().unwrap(),
-                        source: yt_links,
-                        cover: Some(cover_links),
-                        chords: form_data.get("chords").as_string().unwrap(),
-                    })
-                    .expect("This will work")
-                    .send()
-                    .await;
-
-                navigator.push(&Route::SongList);
+                let result = api::client().create_song(&song_form.song).await;
+                if song_form.saved(result, &errors, &notifier).is_some() {
+                    notifier.success("Song added");
+                    navigator.push(&Route::SongList);
+                }
             });
         })
     };
 
     html! {
     <article>
-        <form onsubmit={onsubmit}>
-            <input name="song_title" placeholder={"song title"} />
+        <form ref={form_ref} onsubmit={onsubmit} oninput={oninput}>
+            <input
+                name="song_title"
+                placeholder={"song title"}
+                aria-invalid={ errors.get("song_title").map(|_| "true") }
+            />
+            { error_hint(&errors, "song_title") }
             <fieldset>
                 <legend>{"Sources links"}</legend>
                 <LinkWithDisplayTitle
@@ -225,7 +466,9 @@ pub fn add_song_form() -> Html {
                     on_add={on_yt_link_add.clone()}
                     on_remove={on_yt_link_rm.clone()}
                     min_entries_count={1}
+                    errors={(*errors).clone()}
                 />
+                { error_hint(&errors, "source") }
             </fieldset>
             <fieldset>
                 <legend>{"Covers"}</legend>
@@ -234,11 +477,19 @@ pub fn add_song_form() -> Html {
                     entries_list={(*covers_links_state).clone()}
                     on_add={on_cover_add.clone()}
                     on_remove={on_cover_rm.clone()}
+                    errors={(*errors).clone()}
                 />
             </fieldset>
             <input name="chords" placeholder={"chords, ex: b G D A"} />
+            <textarea
+                name="chordpro"
+                rows="12"
+                placeholder={CHORDPRO_PLACEHOLDER}
+                aria-invalid={ errors.get("chordpro").map(|_| "true") }
+            ></textarea>
+            { error_hint(&errors, "chordpro") }
 
-            <button type={"submit"} >{ "Add new song" }</button>
+            <button type={"submit"} disabled={*invalid}>{ "Add new song" }</button>
         </form>
     </article>
         }
@@ -0,0 +1,122 @@
+use setlistrs_types::{SongDetails as SongDetailsModel, YTLinkDetails};
+use yew::{
+    function_component, html, use_effect_with_deps, use_state, Callback, Html, MouseEvent,
+    Properties,
+};
+use yew_router::prelude::use_navigator;
+
+use crate::api;
+use crate::app::Route;
+use crate::components::{use_notifier, ChordProView};
+
+#[derive(Debug, Clone, Eq, PartialEq, Properties)]
+pub struct SongDetailsProps {
+    pub seed: i64,
+}
+
+#[function_component(SongDetails)]
+pub fn song_details(props: &SongDetailsProps) -> Html {
+    let song = use_state(|| None);
+    let song_id = props.seed;
+    let notifier = use_notifier();
+    {
+        let song = song.clone();
+        use_effect_with_deps(
+            move |_| {
+                let song = song.clone();
+                let notifier = notifier.clone();
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Some(s) = notifier.ok(api::client().get_song(song_id).await) {
+                        song.set(Some(s));
+                    }
+                });
+
+                || ()
+            },
+            song_id,
+        );
+    }
+
+    html! {
+        match &*song {
+            Some(song) => html! { <SongDetailsContent song={song.clone()} /> },
+            None => html! { <p> { "Fetching data..." } </p> }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct SongDetailsContentProps {
+    pub song: SongDetailsModel,
+}
+
+#[function_component(SongDetailsContent)]
+pub fn song_details_content(SongDetailsContentProps { song }: &SongDetailsContentProps) -> Html {
+    let on_edit_click = {
+        let navigator = use_navigator().expect("There is no reason this would not work");
+        let song_id = song.id;
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            navigator.push(&Route::SongEdit { id: song_id });
+        })
+    };
+
+    html! {
+        <article>
+            <header>
+                <h2>{ &song.name }</h2>
+            </header>
+            <h4>{ "Sources" }</h4>
+            <LinksList links={song.source.clone()} />
+            <h4>{ "Covers" }</h4>
+            <LinksList links={song.cover.clone().unwrap_or_default()} />
+            <h4>{ "Chords" }</h4>
+            <pre>{ &song.chords }</pre>
+            if let Some(chordpro) = &song.chordpro {
+                <h4>{ "Lyrics" }</h4>
+                <ChordProView body={chordpro.clone()} />
+            }
+            <footer>
+                <div class="grid">
+                    <button onclick={on_edit_click}>{ "Edit" }</button>
+                    <a
+                        role="button"
+                        class="secondary"
+                        target="_blank"
+                        href={api::client().song_songbook_url(song.id, 0)}
+                    >{ "Download PDF" }</a>
+                </div>
+            </footer>
+        </article>
+    }
+}
+
+#[derive(Clone, PartialEq, Properties)]
+struct LinksListProps {
+    pub links: Vec<YTLinkDetails>,
+}
+
+#[function_component(LinksList)]
+fn links_list(LinksListProps { links }: &LinksListProps) -> Html {
+    if links.is_empty() {
+        return html! { <p>{ "-" }</p> };
+    }
+
+    html! {
+        <ul>
+        {
+            for links.iter().map(|link| html! {
+                <li>
+                    <a href={ link.url.clone() } target="_blank">{
+                        match link.display_title.clone() {
+                            Some(display_title) => display_title,
+                            None => link.url.clone(),
+                        }
+                    }</a>
+                </li>
+            })
+        }
+        </ul>
+    }
+}
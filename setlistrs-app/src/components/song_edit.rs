@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+
+use setlistrs_types::{SongDetails as SongDetailsModel, YTLink};
+use web_sys::{FormData, HtmlFormElement};
+use yew::prelude::*;
+use yew_router::prelude::use_navigator;
+
+use crate::api;
+use crate::app::Route;
+use crate::components::song_add::{
+    error_hint, LinkWithDisplayTitle, LinkWithDisplayTitleAction, LinkWithDisplayTitleIdList,
+    SongForm, CHORDPRO_PLACEHOLDER,
+};
+use crate::components::use_notifier;
+
+#[derive(Debug, Clone, Eq, PartialEq, Properties)]
+pub struct SongEditProps {
+    pub seed: i64,
+}
+
+#[function_component(SongEdit)]
+pub fn song_edit(props: &SongEditProps) -> Html {
+    let song = use_state(|| None);
+    let song_id = props.seed;
+    let notifier = use_notifier();
+    {
+        let song = song.clone();
+        use_effect_with_deps(
+            move |_| {
+                let song = song.clone();
+                let notifier = notifier.clone();
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Some(s) = notifier.ok(api::client().get_song(song_id).await) {
+                        song.set(Some(s));
+                    }
+                });
+
+                || ()
+            },
+            song_id,
+        );
+    }
+
+    html! {
+        match &*song {
+            Some(song) => html! { <SongEditForm song={song.clone()} /> },
+            None => html! { <p> { "Fetching data..." } </p> }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct SongEditFormProps {
+    pub song: SongDetailsModel,
+}
+
+#[function_component(SongEditForm)]
+pub fn song_edit_form(SongEditFormProps { song }: &SongEditFormProps) -> Html {
+    let sources: Vec<YTLink> = song.source.iter().cloned().map(YTLink::from).collect();
+    let covers: Vec<YTLink> = song
+        .cover
+        .iter()
+        .flatten()
+        .cloned()
+        .map(YTLink::from)
+        .collect();
+
+    let (initial_yt_links, yt_links_values) = LinkWithDisplayTitleIdList::for_links(&sources);
+    let (initial_covers_links, covers_links_values) =
+        LinkWithDisplayTitleIdList::for_links(&covers);
+
+    let yt_links_state = use_reducer(|| initial_yt_links);
+    let covers_links_state = use_reducer(|| initial_covers_links);
+    let navigator = use_navigator().expect("There is no reason it will not work.");
+    let form_ref = use_node_ref();
+    let errors = use_state(HashMap::<String, String>::new);
+    let invalid = use_state(|| false);
+    let notifier = use_notifier();
+
+    let on_yt_link_add = {
+        let yt_links_state = yt_links_state.clone();
+        Callback::from(move |_: ()| yt_links_state.dispatch(LinkWithDisplayTitleAction::Add()))
+    };
+    let on_yt_link_rm = {
+        let yt_links_state = yt_links_state.clone();
+        Callback::from(move |entity_id: i32| {
+            yt_links_state.dispatch(LinkWithDisplayTitleAction::Remove(entity_id))
+        })
+    };
+
+    let on_cover_add = {
+        let covers_links_state = covers_links_state.clone();
+        Callback::from(move |_: ()| covers_links_state.dispatch(LinkWithDisplayTitleAction::Add()))
+    };
+    let on_cover_rm = {
+        let covers_links_state = covers_links_state.clone();
+        Callback::from(move |entity_id: i32| {
+            covers_links_state.dispatch(LinkWithDisplayTitleAction::Remove(entity_id))
+        })
+    };
+
+    // Song is filled in already, so errors are shown as soon as they appear.
+    let validate = {
+        let form_ref = form_ref.clone();
+        let yt_links_state = yt_links_state.clone();
+        let covers_links_state = covers_links_state.clone();
+        let errors = errors.clone();
+        let invalid = invalid.clone();
+
+        Callback::from(move |_: ()| {
+            let form = match form_ref.cast::<HtmlFormElement>() {
+                Some(form) => form,
+                None => return,
+            };
+            let form_data =
+                FormData::new_with_form(&form).expect("This will work since form exists.");
+            let input_errors =
+                SongForm::read(&form_data, &yt_links_state, &covers_links_state).validate();
+            invalid.set(!input_errors.is_empty());
+            errors.set(input_errors);
+        })
+    };
+
+    {
+        let validate = validate.clone();
+        use_effect_with_deps(
+            move |_| {
+                validate.emit(());
+                || ()
+            },
+            ((*yt_links_state).clone(), (*covers_links_state).clone()),
+        );
+    }
+
+    let oninput = Callback::from(move |_: InputEvent| validate.emit(()));
+
+    let onsubmit = {
+        let yt_links_state = yt_links_state.clone();
+        let covers_links_state = covers_links_state.clone();
+        let errors = errors.clone();
+        let invalid = invalid.clone();
+        let song_id = song.id;
+
+        Callback::from(move |e: SubmitEvent| {
+            let navigator = navigator.clone();
+            let notifier = notifier.clone();
+            e.prevent_default();
+
+            let form_data: FormData = FormData::new_with_form(&e.target_unchecked_into())
+                .expect("This will work since we have only one form.");
+
+            let song_form = SongForm::read(
+                &form_data,
+                yt_links_state.deref(),
+                covers_links_state.deref(),
+            );
+            let input_errors = song_form.validate();
+            invalid.set(!input_errors.is_empty());
+            errors.set(input_errors.clone());
+            if !input_errors.is_empty() {
+                return;
+            }
+
+            let errors = errors.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = api::client().replace_song(song_id, &song_form.song).await;
+                if song_form.saved(result, &errors, &notifier).is_some() {
+                    notifier.success("Song saved");
+                    navigator.push(&Route::SongDetails { id: song_id });
+                }
+            });
+        })
+    };
+
+    html! {
+    <article>
+        <form ref={form_ref} onsubmit={onsubmit} oninput={oninput}>
+            <input
+                name="song_title"
+                placeholder={"song title"}
+                value={song.name.clone()}
+                aria-invalid={ errors.get("song_title").map(|_| "true") }
+            />
+            { error_hint(&errors, "song_title") }
+            <fieldset>
+                <legend>{"Sources links"}</legend>
+                <LinkWithDisplayTitle
+                    input_name_prefix={"yt"}
+                    entries_list={(*yt_links_state).clone()}
+                    on_add={on_yt_link_add.clone()}
+                    on_remove={on_yt_link_rm.clone()}
+                    min_entries_count={1}
+                    initial_values={yt_links_values}
+                    errors={(*errors).clone()}
+                />
+                { error_hint(&errors, "source") }
+            </fieldset>
+            <fieldset>
+                <legend>{"Covers"}</legend>
+                <LinkWithDisplayTitle
+                    input_name_prefix={"cover"}
+                    entries_list={(*covers_links_state).clone()}
+                    on_add={on_cover_add.clone()}
+                    on_remove={on_cover_rm.clone()}
+                    initial_values={covers_links_values}
+                    errors={(*errors).clone()}
+                />
+            </fieldset>
+            <input name="chords" placeholder={"chords, ex: b G D A"} value={song.chords.clone()} />
+            <textarea
+                name="chordpro"
+                rows="12"
+                placeholder={CHORDPRO_PLACEHOLDER}
+                value={song.chordpro.clone().unwrap_or_default()}
+                aria-invalid={ errors.get("chordpro").map(|_| "true") }
+            ></textarea>
+            { error_hint(&errors, "chordpro") }
+
+            <button type={"submit"} disabled={*invalid}>{ "Save song" }</button>
+        </form>
+    </article>
+        }
+}
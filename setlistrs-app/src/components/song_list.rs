@@ -1,12 +1,16 @@
-use gloo_net::http::Request;
-
+use setlistrs_types::error::ErrorCode;
 use setlistrs_types::Song;
 use setlistrs_types::SongList;
 
 use web_sys::HtmlElement;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
+use yew_router::prelude::use_navigator;
 
+use crate::api;
+use crate::app::Route;
+use crate::components::use_notifier;
+use crate::components::NotificationAction;
 use crate::components::SongChockboxAction;
 use crate::components::SongsForSetlist;
 
@@ -37,11 +41,7 @@ impl Reducible for SonglistContent {
 impl SonglistContent {
     pub fn new(songlist: SongList) -> Self {
         Self {
-            songs: songlist
-                .data
-                .into_iter()
-                .map(|(song_id, song)| (song_id.clone(), song.clone()))
-                .collect(),
+            songs: songlist.data,
         }
     }
 }
@@ -49,24 +49,29 @@ impl SonglistContent {
 #[function_component(SongsList)]
 pub fn all_songs_list() -> Html {
     let setlist = use_reducer(|| SonglistContent { songs: vec![] });
+    let navigator = use_navigator().expect("There is no reason it will not work.");
+    let notifier = use_notifier();
 
     {
         let setlist = setlist.clone();
+        let notifier = notifier.clone();
         use_effect_with_deps(
             move |_| {
                 let setlist = setlist.clone();
+                let navigator = navigator.clone();
+                let notifier = notifier.clone();
 
                 wasm_bindgen_futures::spawn_local(async move {
-                    let response = Request::get("http://127.0.0.1:8081/songs").send().await;
-                    match response {
-                        Ok(response) => {
-                            let possible_setlist: Result<SongList, _> = response.json().await;
-                            match possible_setlist {
-                                Ok(s) => setlist.dispatch(SonglistContentAction::PutSongs(s)),
-                                Err(_) => panic!(), // TODO -> handle it gracefuly
+                    match api::client().list_songs().await {
+                        // Anonymous visitors have nothing to see, login first.
+                        Err(e) if e.has_code(ErrorCode::Unauthorized) => {
+                            navigator.push(&Route::UserLogin)
+                        }
+                        result => {
+                            if let Some(s) = notifier.ok(result) {
+                                setlist.dispatch(SonglistContentAction::PutSongs(s))
                             }
                         }
-                        Err(_) => panic!(), // TODO -> handle it gracefuly
                     };
                 });
 
@@ -94,9 +99,26 @@ pub fn all_songs_list() -> Html {
         })
     };
 
+    let on_song_details_click = {
+        let navigator = use_navigator().expect("There is no reason this would not work");
+        Callback::from(move |event: MouseEvent| {
+            event.prevent_default();
+            let button: HtmlElement = event.target_unchecked_into();
+            match button.get_attribute("data-song-id") {
+                Some(song_id) => navigator.push(&Route::SongDetails {
+                    id: song_id
+                        .parse::<i64>()
+                        .expect("id always will be there as numeric"),
+                }),
+                None => panic!(),
+            };
+        })
+    };
+
     let on_song_delete_click = {
         let songs_for_setlist = songs_for_setlist.clone();
         let setlist = setlist.clone();
+        let notifier = notifier.clone();
 
         Callback::from(move |event: MouseEvent| {
             event.prevent_default();
@@ -108,20 +130,39 @@ pub fn all_songs_list() -> Html {
 
             let songs_for_setlist = songs_for_setlist.clone();
             let setlist = setlist.clone();
+            let notifier = notifier.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                let response =
-                    Request::delete(format!("http://127.0.0.1:8081/songs/{}", song_id).as_str())
-                        .send()
-                        .await;
-                match response {
-                    Ok(response) => {
-                        if response.status() == 204 {
-                            songs_for_setlist.dispatch(SongChockboxAction::Remove(song_id));
-                            setlist.dispatch(SonglistContentAction::RemoveSong(song_id));
-                        }
-                    }
-                    Err(_) => panic!(), // TODO -> handle it gracefuly
-                };
+                let result = api::client().delete_song(song_id).await;
+                if notifier.ok(result).is_some() {
+                    songs_for_setlist.dispatch(SongChockboxAction::Remove(song_id));
+                    setlist.dispatch(SonglistContentAction::RemoveSong(song_id));
+
+                    let on_undo = {
+                        let setlist = setlist.clone();
+                        let notifier = notifier.clone();
+                        Callback::from(move |_| {
+                            let setlist = setlist.clone();
+                            let notifier = notifier.clone();
+                            wasm_bindgen_futures::spawn_local(async move {
+                                let client = api::client();
+                                if notifier.ok(client.restore_song(song_id).await).is_none() {
+                                    return;
+                                }
+                                // Song is put back at its place in the list.
+                                if let Some(s) = notifier.ok(client.list_songs().await) {
+                                    setlist.dispatch(SonglistContentAction::PutSongs(s));
+                                }
+                            });
+                        })
+                    };
+                    notifier.success_with_action(
+                        "Song moved to trash",
+                        NotificationAction {
+                            label: "Undo".to_string(),
+                            on_click: on_undo,
+                        },
+                    );
+                }
             });
         })
     };
@@ -130,8 +171,8 @@ pub fn all_songs_list() -> Html {
     <>
         <article>
             <crate::components::SetlistAdd
-                songs={(*songs_for_setlist).data.clone()}
-                disabled={&(*songs_for_setlist).data.is_empty()}
+                songs={songs_for_setlist.data.clone()}
+                disabled={&songs_for_setlist.data.is_empty()}
             />
         </article>
         <table>
@@ -146,7 +187,7 @@ pub fn all_songs_list() -> Html {
             <tbody>
             {
                 html! {
-                    for (*setlist).songs.iter().map(|( song_id, song )|
+                    for setlist.songs.iter().map(|( song_id, song )|
                     html! {
                         <tr data-song_id={ format!("{}", song_id) }>
                             <td><input type="checkbox" name="song" onclick={ on_song_checkbox_click.clone() } value={ format!("{}", song_id) }/></td>
@@ -189,7 +230,10 @@ pub fn all_songs_list() -> Html {
                                 }
                             }</td>
                             <td>{ song.chords.clone() }</td>
-                            <td><button onclick={ on_song_delete_click.clone() } data-song-id={ format!("{}", song_id) } class={classes!("red-bg-bd")}>{"Delete"}</button></td>
+                            <td>
+                                <button onclick={ on_song_details_click.clone() } data-song-id={ format!("{}", song_id) }>{"Details / Edit"}</button>
+                                <button onclick={ on_song_delete_click.clone() } data-song-id={ format!("{}", song_id) } class={classes!("red-bg-bd")}>{"Delete"}</button>
+                            </td>
                         </tr>
                     })
                 }
@@ -0,0 +1,145 @@
+use setlistrs_types::DeletedSong;
+use std::rc::Rc;
+use web_sys::MouseEvent;
+
+use yew::{
+    classes, function_component, html, use_effect_with_deps, use_reducer, Callback, Html, Reducible,
+};
+
+use crate::api;
+use crate::components::use_notifier;
+
+enum TrashAction {
+    Load(Vec<DeletedSong>),
+    /// Song was restored or purged.
+    Remove(i64),
+}
+
+#[derive(Default)]
+struct Trash {
+    songs: Vec<DeletedSong>,
+}
+
+impl Reducible for Trash {
+    type Action = TrashAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            TrashAction::Load(songs) => Trash { songs }.into(),
+            TrashAction::Remove(song_id) => {
+                let songs = self
+                    .songs
+                    .iter()
+                    .filter(|song| song.id != song_id)
+                    .cloned()
+                    .collect();
+
+                Trash { songs }.into()
+            }
+        }
+    }
+}
+
+/// Deleted songs, they can be restored or removed for good.
+#[function_component(SongTrash)]
+pub fn song_trash() -> Html {
+    let trash = use_reducer(Trash::default);
+    let notifier = use_notifier();
+
+    {
+        let trash = trash.clone();
+        let notifier = notifier.clone();
+        use_effect_with_deps(
+            move |_| {
+                let trash = trash.clone();
+                let notifier = notifier.clone();
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Some(s) = notifier.ok(api::client().list_trash().await) {
+                        trash.dispatch(TrashAction::Load(s.data));
+                    }
+                });
+
+                || ()
+            },
+            (),
+        );
+    }
+
+    html! {
+        <table>
+            <caption>{ "Trash" }</caption>
+            <thead>
+                <th>{ "Song" }</th>
+                <th>{ "Deleted" }</th>
+                <th>{ "Action" }</th>
+            </thead>
+            <tbody>
+            if trash.songs.is_empty() {
+                <tr>
+                    <td colspan="3">{ "Trash is empty." }</td>
+                </tr>
+            }
+            {
+                for trash.songs.iter().map(|song| {
+                    let on_restore_click = {
+                        let trash = trash.clone();
+                        let song_id = song.id;
+                        let notifier = notifier.clone();
+                        Callback::from(move |e: MouseEvent| {
+                            e.prevent_default();
+                            let trash = trash.clone();
+                            let notifier = notifier.clone();
+                            wasm_bindgen_futures::spawn_local(async move {
+                                let result = api::client().restore_song(song_id).await;
+                                if notifier.ok(result).is_some() {
+                                    trash.dispatch(TrashAction::Remove(song_id));
+                                    notifier.success("Song restored");
+                                }
+                            });
+                        })
+                    };
+                    let on_purge_click = {
+                        let trash = trash.clone();
+                        let song_id = song.id;
+                        let notifier = notifier.clone();
+                        Callback::from(move |e: MouseEvent| {
+                            e.prevent_default();
+                            let trash = trash.clone();
+                            let notifier = notifier.clone();
+                            wasm_bindgen_futures::spawn_local(async move {
+                                let result = api::client().purge_song(song_id).await;
+                                if notifier.ok(result).is_some() {
+                                    trash.dispatch(TrashAction::Remove(song_id));
+                                    notifier.success("Song deleted for good");
+                                }
+                            });
+                        })
+                    };
+
+                    html! {
+                        <tr>
+                            <td>{ &song.name }</td>
+                            <td>{ deleted_on(song.deleted_at) }</td>
+                            <td>
+                                <button onclick={on_restore_click}>{"Restore"}</button>
+                                <button
+                                    onclick={on_purge_click}
+                                    class={classes!("red-bg-bd")}
+                                    title="Song is removed from setlists as well"
+                                >{"Delete for good"}</button>
+                            </td>
+                        </tr>
+                    }
+                })
+            }
+            </tbody>
+        </table>
+    }
+}
+
+fn deleted_on(deleted_at: i64) -> String {
+    let date = js_sys::Date::new(&(deleted_at as f64 * 1000.0).into());
+    let date: String = date.to_iso_string().into();
+    date[..10].to_string()
+}
@@ -0,0 +1,64 @@
+use setlistrs_types::Credentials;
+use web_sys::{FormData, SubmitEvent};
+use yew::callback::Callback;
+use yew::{function_component, html, use_state, Html, TargetCast};
+use yew_router::prelude::use_navigator;
+
+use crate::api;
+use crate::app::Route;
+use crate::components::use_notifier;
+
+#[function_component(UserRegister)]
+pub fn user_register() -> Html {
+    let navigator = use_navigator().expect("There is no reason it will not work.");
+    let error = use_state(|| None::<String>);
+    let notifier = use_notifier();
+
+    let on_register_submit = {
+        let error = error.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            let navigator = navigator.clone();
+            let error = error.clone();
+            let notifier = notifier.clone();
+            e.prevent_default();
+            let form_data = FormData::new_with_form(&e.target_unchecked_into())
+                .expect("This is going to work since we are sure form exists.");
+
+            let credentials = Credentials {
+                username: form_data.get("username").as_string().unwrap_or_default(),
+                password: form_data.get("password").as_string().unwrap_or_default(),
+            };
+
+            wasm_bindgen_futures::spawn_local(async move {
+                match api::client().register(&credentials).await {
+                    Ok(_) => navigator.push(&Route::UserLogin),
+                    // Errors of the credentials are shown in the form.
+                    Err(e) => match e.api_error() {
+                        Some(api_error) => error.set(Some(api_error.message.clone())),
+                        None => notifier.error(e.to_string()),
+                    },
+                };
+            });
+        })
+    };
+
+    html! {
+        <>
+            <h2>{"Register"}</h2>
+            <form onsubmit={ on_register_submit }>
+                <label for="username">{"Username"}</label>
+                <input type="text" name="username" id="username" autocomplete="username"/>
+                <label for="password">
+                    {"Password"}
+                    <input type="password" name="password" id="password" autocomplete="new-password"/>
+                    <small>{"At least 8 characters."}</small>
+                </label>
+                if let Some(error) = (*error).clone() {
+                    <small>{ error }</small>
+                }
+                <button type="submit">{"Register"}</button>
+            </form>
+        </>
+    }
+}
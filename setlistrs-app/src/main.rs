@@ -1,7 +1,11 @@
+mod api;
 mod app;
 
 mod components;
 
 fn main() {
-    yew::Renderer::<app::App>::new().render();
+    wasm_bindgen_futures::spawn_local(async {
+        api::load_config().await;
+        yew::Renderer::<app::App>::new().render();
+    });
 }
@@ -0,0 +1,50 @@
+use std::fmt;
+
+use setlistrs_types::error::{ApiError, ErrorCode};
+
+#[derive(Debug)]
+pub enum Error {
+    /// Server could not be reached or response could not be read.
+    Transport(String),
+    /// Server responded with `ApiError`.
+    Api(ApiError),
+    /// Response is not what the API sends, e.g. error page of a proxy.
+    UnexpectedResponse { status: u16, message: String },
+}
+
+impl Error {
+    /// `ApiError` the server responded with, e.g. to show its field errors.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Error::Api(api_error) => Some(api_error),
+            _ => None,
+        }
+    }
+
+    /// Whether server responded with `ApiError` of `code`, e.g. to send user
+    /// to login page on `ErrorCode::Unauthorized`.
+    pub fn has_code(&self, code: ErrorCode) -> bool {
+        self.api_error()
+            .is_some_and(|api_error| api_error.code == code)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Transport(message) => write!(f, "Server can not be reached, {}", message),
+            Error::Api(api_error) => write!(f, "{}", api_error),
+            Error::UnexpectedResponse { status, message } => {
+                write!(f, "Server sent unexpected response, {} {}", status, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ApiError> for Error {
+    fn from(api_error: ApiError) -> Self {
+        Error::Api(api_error)
+    }
+}
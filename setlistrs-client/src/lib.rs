@@ -0,0 +1,731 @@
+//! Typed client of the setlistrs API. Requests are sent by a `Transport`,
+//! enable feature `gloo` in the browser or `reqwest` in native programs.
+//!
+//! ```ignore
+//! let client = Client::new("http://127.0.0.1:8081", ReqwestTransport::new());
+//! let session = client.login(&credentials).await?;
+//! let client = client.with_token(session.token);
+//! let songs = client.list_songs().await?;
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use setlistrs_types::error::ApiError;
+use setlistrs_types::{
+    Band, BandDetails, BandInvite, BandInviteList, BandJoin, BandList, BandMemberPatch,
+    Credentials, DeletedSongList, NewBand, NewBandInvite, NewSetlist, NewSetlistShare,
+    NewSetlistSong, Session, Setlist, SetlistList, SetlistOrder, SetlistPatch, SetlistShare,
+    SetlistShareList, SetlistSongOverridesPatch, Song, SongDetails, SongList, SongPatch, User,
+};
+
+mod error;
+pub mod transport;
+
+pub use error::Error;
+use transport::{Method, Request, Response, Transport};
+
+#[derive(Clone, Debug)]
+pub struct Client<T> {
+    transport: T,
+    base_url: String,
+    token: Option<String>,
+    band: Option<i64>,
+}
+
+impl<T: Transport> Client<T> {
+    pub fn new(base_url: impl Into<String>, transport: T) -> Self {
+        Self {
+            transport,
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            token: None,
+            band: None,
+        }
+    }
+
+    /// Token of `Session`, not needed in the browser which sends the cookie.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Songs and setlists are then those of the band instead of personal
+    /// ones, see `?band=<id>`.
+    pub fn with_band(mut self, band: Option<i64>) -> Self {
+        self.band = band;
+        self
+    }
+
+    /// Absolute URL of API `path`, e.g. for links to exported PDFs.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    // Users
+
+    pub async fn register(&self, credentials: &Credentials) -> Result<User, Error> {
+        self.json(Method::Post, "/register".to_owned(), Some(credentials))
+            .await
+    }
+
+    pub async fn login(&self, credentials: &Credentials) -> Result<Session, Error> {
+        self.json(Method::Post, "/login".to_owned(), Some(credentials))
+            .await
+    }
+
+    pub async fn logout(&self) -> Result<(), Error> {
+        self.empty(Method::Post, "/logout".to_owned(), None::<&()>)
+            .await
+    }
+
+    pub async fn me(&self) -> Result<User, Error> {
+        self.json(Method::Get, "/me".to_owned(), None::<&()>).await
+    }
+
+    // Songs
+
+    pub async fn list_songs(&self) -> Result<SongList, Error> {
+        let path = self.library_path("/songs", None);
+        self.json(Method::Get, path, None::<&()>).await
+    }
+
+    pub async fn get_song(&self, song_id: i64) -> Result<SongDetails, Error> {
+        self.get_transposed_song(song_id, 0).await
+    }
+
+    /// Chords of song transposed by `semitones`.
+    pub async fn get_transposed_song(
+        &self,
+        song_id: i64,
+        semitones: i32,
+    ) -> Result<SongDetails, Error> {
+        let path = self.library_path(&format!("/songs/{}", song_id), transpose(semitones));
+        self.json(Method::Get, path, None::<&()>).await
+    }
+
+    pub async fn create_song(&self, song: &Song) -> Result<Song, Error> {
+        let path = self.library_path("/songs", None);
+        self.json(Method::Post, path, Some(song)).await
+    }
+
+    pub async fn replace_song(&self, song_id: i64, song: &Song) -> Result<SongDetails, Error> {
+        let path = self.library_path(&format!("/songs/{}", song_id), None);
+        self.json(Method::Put, path, Some(song)).await
+    }
+
+    pub async fn update_song(
+        &self,
+        song_id: i64,
+        song_patch: &SongPatch,
+    ) -> Result<SongDetails, Error> {
+        let path = self.library_path(&format!("/songs/{}", song_id), None);
+        self.json(Method::Patch, path, Some(song_patch)).await
+    }
+
+    pub async fn delete_song(&self, song_id: i64) -> Result<(), Error> {
+        let path = self.library_path(&format!("/songs/{}", song_id), None);
+        self.empty(Method::Delete, path, None::<&()>).await
+    }
+
+    /// Songs moved to trash by `delete_song`.
+    pub async fn list_trash(&self) -> Result<DeletedSongList, Error> {
+        let path = self.library_path("/songs/trash", None);
+        self.json(Method::Get, path, None::<&()>).await
+    }
+
+    pub async fn restore_song(&self, song_id: i64) -> Result<SongDetails, Error> {
+        let path = self.library_path(&format!("/songs/{}/restore", song_id), None);
+        self.json(Method::Post, path, None::<&()>).await
+    }
+
+    /// Removes song for good, together with its entries in setlists.
+    pub async fn purge_song(&self, song_id: i64) -> Result<(), Error> {
+        let path = self.library_path(
+            &format!("/songs/{}", song_id),
+            Some("purge=true".to_owned()),
+        );
+        self.empty(Method::Delete, path, None::<&()>).await
+    }
+
+    pub async fn export_song_songbook(
+        &self,
+        song_id: i64,
+        semitones: i32,
+    ) -> Result<Vec<u8>, Error> {
+        self.binary(self.song_songbook_path(song_id, semitones))
+            .await
+    }
+
+    /// Absolute URL of `export_song_songbook`, for links.
+    pub fn song_songbook_url(&self, song_id: i64, semitones: i32) -> String {
+        self.url(&self.song_songbook_path(song_id, semitones))
+    }
+
+    fn song_songbook_path(&self, song_id: i64, semitones: i32) -> String {
+        self.library_path(
+            &format!("/songs/{}/songbook.pdf", song_id),
+            transpose(semitones),
+        )
+    }
+
+    // Setlists
+
+    pub async fn list_setlists(&self) -> Result<SetlistList, Error> {
+        let path = self.library_path("/setlists", None);
+        self.json(Method::Get, path, None::<&()>).await
+    }
+
+    pub async fn list_archived_setlists(&self) -> Result<SetlistList, Error> {
+        let path = self.library_path("/setlists", Some("archived=true".to_owned()));
+        self.json(Method::Get, path, None::<&()>).await
+    }
+
+    pub async fn get_setlist(&self, setlist_id: i64) -> Result<Setlist, Error> {
+        self.get_transposed_setlist(setlist_id, 0).await
+    }
+
+    /// Chords of all songs in setlist transposed by `semitones`, on top of
+    /// transposition of single songs.
+    pub async fn get_transposed_setlist(
+        &self,
+        setlist_id: i64,
+        semitones: i32,
+    ) -> Result<Setlist, Error> {
+        let path = self.library_path(&format!("/setlists/{}", setlist_id), transpose(semitones));
+        self.json(Method::Get, path, None::<&()>).await
+    }
+
+    /// Gives id of the created setlist.
+    pub async fn create_setlist(&self, new_setlist: &NewSetlist) -> Result<i64, Error> {
+        let path = self.library_path("/setlists", None);
+        self.json(Method::Post, path, Some(new_setlist)).await
+    }
+
+    pub async fn update_setlist(
+        &self,
+        setlist_id: i64,
+        setlist_patch: &SetlistPatch,
+    ) -> Result<Setlist, Error> {
+        let path = self.library_path(&format!("/setlists/{}", setlist_id), None);
+        self.json(Method::Patch, path, Some(setlist_patch)).await
+    }
+
+    pub async fn reorder_setlist(
+        &self,
+        setlist_id: i64,
+        setlist_order: &SetlistOrder,
+    ) -> Result<Setlist, Error> {
+        let path = self.library_path(&format!("/setlists/{}/order", setlist_id), None);
+        self.json(Method::Put, path, Some(setlist_order)).await
+    }
+
+    pub async fn delete_setlist(&self, setlist_id: i64) -> Result<(), Error> {
+        let path = self.library_path(&format!("/setlists/{}", setlist_id), None);
+        self.empty(Method::Delete, path, None::<&()>).await
+    }
+
+    pub async fn archive_setlist(&self, setlist_id: i64) -> Result<(), Error> {
+        let path = self.library_path(&format!("/setlists/{}/archive", setlist_id), None);
+        self.empty(Method::Post, path, None::<&()>).await
+    }
+
+    pub async fn unarchive_setlist(&self, setlist_id: i64) -> Result<(), Error> {
+        let path = self.library_path(&format!("/setlists/{}/unarchive", setlist_id), None);
+        self.empty(Method::Post, path, None::<&()>).await
+    }
+
+    pub async fn add_setlist_song(
+        &self,
+        setlist_id: i64,
+        new_setlist_song: &NewSetlistSong,
+    ) -> Result<Setlist, Error> {
+        let path = self.library_path(&format!("/setlists/{}/songs", setlist_id), None);
+        self.json(Method::Post, path, Some(new_setlist_song)).await
+    }
+
+    pub async fn update_setlist_song(
+        &self,
+        setlist_id: i64,
+        song_id: i64,
+        overrides_patch: &SetlistSongOverridesPatch,
+    ) -> Result<Setlist, Error> {
+        let path = self.library_path(&format!("/setlists/{}/songs/{}", setlist_id, song_id), None);
+        self.json(Method::Patch, path, Some(overrides_patch)).await
+    }
+
+    pub async fn remove_setlist_song(&self, setlist_id: i64, song_id: i64) -> Result<(), Error> {
+        let path = self.library_path(&format!("/setlists/{}/songs/{}", setlist_id, song_id), None);
+        self.empty(Method::Delete, path, None::<&()>).await
+    }
+
+    pub async fn export_setlist_pdf(
+        &self,
+        setlist_id: i64,
+        semitones: i32,
+        chords: bool,
+    ) -> Result<Vec<u8>, Error> {
+        self.binary(self.setlist_pdf_path(setlist_id, semitones, chords))
+            .await
+    }
+
+    /// Absolute URL of `export_setlist_pdf`, for links.
+    pub fn setlist_pdf_url(&self, setlist_id: i64, semitones: i32, chords: bool) -> String {
+        self.url(&self.setlist_pdf_path(setlist_id, semitones, chords))
+    }
+
+    fn setlist_pdf_path(&self, setlist_id: i64, semitones: i32, chords: bool) -> String {
+        let mut query = format!("chords={}", chords);
+        if let Some(transpose) = transpose(semitones) {
+            query = format!("{}&{}", query, transpose);
+        }
+        self.library_path(&format!("/setlists/{}/export.pdf", setlist_id), Some(query))
+    }
+
+    pub async fn export_setlist_songbook(
+        &self,
+        setlist_id: i64,
+        semitones: i32,
+    ) -> Result<Vec<u8>, Error> {
+        self.binary(self.setlist_songbook_path(setlist_id, semitones))
+            .await
+    }
+
+    /// Absolute URL of `export_setlist_songbook`, for links.
+    pub fn setlist_songbook_url(&self, setlist_id: i64, semitones: i32) -> String {
+        self.url(&self.setlist_songbook_path(setlist_id, semitones))
+    }
+
+    fn setlist_songbook_path(&self, setlist_id: i64, semitones: i32) -> String {
+        self.library_path(
+            &format!("/setlists/{}/songbook.pdf", setlist_id),
+            transpose(semitones),
+        )
+    }
+
+    // Share links
+
+    pub async fn share_setlist(
+        &self,
+        setlist_id: i64,
+        new_share: &NewSetlistShare,
+    ) -> Result<SetlistShare, Error> {
+        let path = self.library_path(&format!("/setlists/{}/share", setlist_id), None);
+        self.json(Method::Post, path, Some(new_share)).await
+    }
+
+    pub async fn list_setlist_shares(&self, setlist_id: i64) -> Result<SetlistShareList, Error> {
+        let path = self.library_path(&format!("/setlists/{}/shares", setlist_id), None);
+        self.json(Method::Get, path, None::<&()>).await
+    }
+
+    pub async fn revoke_setlist_share(&self, setlist_id: i64, token: &str) -> Result<(), Error> {
+        let path = self.library_path(&format!("/setlists/{}/shares/{}", setlist_id, token), None);
+        self.empty(Method::Delete, path, None::<&()>).await
+    }
+
+    /// Needs no login, `token` is the one of share link.
+    pub async fn get_shared_setlist(&self, token: &str) -> Result<Setlist, Error> {
+        self.json(
+            Method::Get,
+            format!("/shared/setlists/{}", token),
+            None::<&()>,
+        )
+        .await
+    }
+
+    // Bands
+
+    pub async fn list_bands(&self) -> Result<BandList, Error> {
+        self.json(Method::Get, "/bands".to_owned(), None::<&()>)
+            .await
+    }
+
+    pub async fn create_band(&self, new_band: &NewBand) -> Result<Band, Error> {
+        self.json(Method::Post, "/bands".to_owned(), Some(new_band))
+            .await
+    }
+
+    pub async fn join_band(&self, band_join: &BandJoin) -> Result<Band, Error> {
+        self.json(Method::Post, "/bands/join".to_owned(), Some(band_join))
+            .await
+    }
+
+    pub async fn get_band(&self, band_id: i64) -> Result<BandDetails, Error> {
+        self.json(Method::Get, format!("/bands/{}", band_id), None::<&()>)
+            .await
+    }
+
+    pub async fn rename_band(
+        &self,
+        band_id: i64,
+        band_patch: &NewBand,
+    ) -> Result<BandDetails, Error> {
+        self.json(
+            Method::Patch,
+            format!("/bands/{}", band_id),
+            Some(band_patch),
+        )
+        .await
+    }
+
+    pub async fn create_band_invite(
+        &self,
+        band_id: i64,
+        new_invite: &NewBandInvite,
+    ) -> Result<BandInvite, Error> {
+        self.json(
+            Method::Post,
+            format!("/bands/{}/invites", band_id),
+            Some(new_invite),
+        )
+        .await
+    }
+
+    pub async fn list_band_invites(&self, band_id: i64) -> Result<BandInviteList, Error> {
+        self.json(
+            Method::Get,
+            format!("/bands/{}/invites", band_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    pub async fn delete_band_invite(&self, band_id: i64, code: &str) -> Result<(), Error> {
+        self.empty(
+            Method::Delete,
+            format!("/bands/{}/invites/{}", band_id, code),
+            None::<&()>,
+        )
+        .await
+    }
+
+    pub async fn update_band_member(
+        &self,
+        band_id: i64,
+        user_id: i64,
+        member_patch: &BandMemberPatch,
+    ) -> Result<BandDetails, Error> {
+        self.json(
+            Method::Patch,
+            format!("/bands/{}/members/{}", band_id, user_id),
+            Some(member_patch),
+        )
+        .await
+    }
+
+    pub async fn remove_band_member(&self, band_id: i64, user_id: i64) -> Result<(), Error> {
+        self.empty(
+            Method::Delete,
+            format!("/bands/{}/members/{}", band_id, user_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Path of songs and setlists, scoped to band of the client.
+    fn library_path(&self, path: &str, query: Option<String>) -> String {
+        let band = self.band.map(|band| format!("band={}", band));
+        let query: Vec<String> = band.into_iter().chain(query).collect();
+        if query.is_empty() {
+            path.to_owned()
+        } else {
+            format!("{}?{}", path, query.join("&"))
+        }
+    }
+
+    async fn json<B: Serialize, R: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: String,
+        body: Option<&B>,
+    ) -> Result<R, Error> {
+        let response = self.send(method, path, body).await?;
+        serde_json::from_slice(&response.body).map_err(|e| Error::UnexpectedResponse {
+            status: response.status,
+            message: e.to_string(),
+        })
+    }
+
+    async fn empty<B: Serialize>(
+        &self,
+        method: Method,
+        path: String,
+        body: Option<&B>,
+    ) -> Result<(), Error> {
+        self.send(method, path, body).await.map(|_| ())
+    }
+
+    async fn binary(&self, path: String) -> Result<Vec<u8>, Error> {
+        let response = self.send(Method::Get, path, None::<&()>).await?;
+        Ok(response.body)
+    }
+
+    /// Responses with non 2xx status are turned into `Error`.
+    async fn send<B: Serialize>(
+        &self,
+        method: Method,
+        path: String,
+        body: Option<&B>,
+    ) -> Result<Response, Error> {
+        let request = Request {
+            method,
+            url: self.url(&path),
+            token: self.token.clone(),
+            body: body.map(|body| {
+                serde_json::to_string(body).expect("Types of setlistrs-types serialize to JSON.")
+            }),
+        };
+        let response = self.transport.send(request).await?;
+        if response.ok() {
+            return Ok(response);
+        }
+
+        match serde_json::from_slice::<ApiError>(&response.body) {
+            Ok(api_error) => Err(Error::Api(api_error)),
+            Err(_) => Err(Error::UnexpectedResponse {
+                status: response.status,
+                message: String::from_utf8_lossy(&response.body).into_owned(),
+            }),
+        }
+    }
+}
+
+fn transpose(semitones: i32) -> Option<String> {
+    (semitones != 0).then(|| format!("transpose={}", semitones))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    use setlistrs_types::error::ErrorCode;
+
+    use super::*;
+
+    const BASE_URL: &str = "http://api.test";
+    const SETLIST: &str = r#"{"display_title":"Gig","songs":[]}"#;
+
+    /// Answers every request with the same response and keeps the requests.
+    struct StubTransport {
+        status: u16,
+        body: &'static str,
+        requests: RefCell<Vec<Request>>,
+    }
+
+    impl Transport for StubTransport {
+        async fn send(&self, request: Request) -> Result<Response, Error> {
+            self.requests.borrow_mut().push(request);
+            Ok(Response {
+                status: self.status,
+                body: self.body.as_bytes().to_vec(),
+            })
+        }
+    }
+
+    fn client(status: u16, body: &'static str) -> Client<StubTransport> {
+        let transport = StubTransport {
+            status,
+            body,
+            requests: RefCell::new(Vec::new()),
+        };
+
+        Client::new(format!("{}/", BASE_URL), transport)
+    }
+
+    /// Stub transport is ready right away, so the future needs no runtime.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    /// Method and path with query of the requests sent so far.
+    fn sent(client: &Client<StubTransport>) -> Vec<(Method, String)> {
+        client
+            .transport
+            .requests
+            .borrow()
+            .iter()
+            .map(|request| {
+                let path = request.url.strip_prefix(BASE_URL).unwrap();
+                (request.method, path.to_owned())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn transposes_only_when_asked() {
+        let client = client(200, SETLIST);
+        block_on(client.get_setlist(3)).unwrap();
+        block_on(client.get_transposed_setlist(3, -2)).unwrap();
+        block_on(client.get_transposed_setlist(3, 0)).unwrap();
+
+        assert_eq!(
+            sent(&client),
+            [
+                (Method::Get, "/setlists/3".to_owned()),
+                (Method::Get, "/setlists/3?transpose=-2".to_owned()),
+                (Method::Get, "/setlists/3".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn scopes_library_to_band() {
+        let client = client(200, SETLIST).with_band(Some(7));
+        block_on(client.get_transposed_setlist(3, 5)).unwrap();
+        block_on(client.add_setlist_song(
+            3,
+            &NewSetlistSong {
+                song_id: 2,
+                position: None,
+            },
+        ))
+        .unwrap();
+
+        assert_eq!(
+            sent(&client),
+            [
+                (Method::Get, "/setlists/3?band=7&transpose=5".to_owned()),
+                (Method::Post, "/setlists/3/songs?band=7".to_owned()),
+            ]
+        );
+        let requests = client.transport.requests.borrow();
+        assert_eq!(
+            requests[1].body.as_deref(),
+            Some(r#"{"song_id":2,"position":null}"#)
+        );
+    }
+
+    #[test]
+    fn lists_archived_setlists() {
+        let client = client(200, r#"{"data":[]}"#);
+        block_on(client.list_setlists()).unwrap();
+        block_on(client.list_archived_setlists()).unwrap();
+        let client = client.with_band(Some(7));
+        block_on(client.list_archived_setlists()).unwrap();
+
+        assert_eq!(
+            sent(&client),
+            [
+                (Method::Get, "/setlists".to_owned()),
+                (Method::Get, "/setlists?archived=true".to_owned()),
+                (Method::Get, "/setlists?band=7&archived=true".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn moves_songs_to_trash_and_purges_them() {
+        let client = client(204, "").with_band(Some(7));
+        block_on(client.delete_song(4)).unwrap();
+        block_on(client.purge_song(4)).unwrap();
+
+        assert_eq!(
+            sent(&client),
+            [
+                (Method::Delete, "/songs/4?band=7".to_owned()),
+                (Method::Delete, "/songs/4?band=7&purge=true".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn builds_export_queries() {
+        let client = client(200, "%PDF");
+        assert_eq!(
+            block_on(client.export_setlist_pdf(3, 0, false)).unwrap(),
+            b"%PDF"
+        );
+        block_on(client.export_setlist_pdf(3, 2, true)).unwrap();
+        block_on(client.export_setlist_songbook(3, -1)).unwrap();
+
+        assert_eq!(
+            sent(&client),
+            [
+                (
+                    Method::Get,
+                    "/setlists/3/export.pdf?chords=false".to_owned()
+                ),
+                (
+                    Method::Get,
+                    "/setlists/3/export.pdf?chords=true&transpose=2".to_owned()
+                ),
+                (
+                    Method::Get,
+                    "/setlists/3/songbook.pdf?transpose=-1".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn scopes_export_urls_to_band() {
+        let client = client(200, "%PDF").with_band(Some(7));
+
+        assert_eq!(
+            client.setlist_pdf_url(3, 0, true),
+            "http://api.test/setlists/3/export.pdf?band=7&chords=true"
+        );
+        assert_eq!(
+            client.setlist_songbook_url(3, 2),
+            "http://api.test/setlists/3/songbook.pdf?band=7&transpose=2"
+        );
+        assert_eq!(
+            client.song_songbook_url(4, 0),
+            "http://api.test/songs/4/songbook.pdf?band=7"
+        );
+    }
+
+    #[test]
+    fn does_not_scope_shared_setlist_to_band() {
+        let client = client(200, SETLIST).with_band(Some(7)).with_token("secret");
+        block_on(client.get_shared_setlist("abc")).unwrap();
+
+        assert_eq!(
+            sent(&client),
+            [(Method::Get, "/shared/setlists/abc".to_owned())]
+        );
+        let requests = client.transport.requests.borrow();
+        assert_eq!(requests[0].token.as_deref(), Some("secret"));
+        assert_eq!(requests[0].body, None);
+    }
+
+    #[test]
+    fn maps_error_responses() {
+        let client = client(
+            422,
+            r#"{"code":"validation","message":"Invalid","fields":[{"field":"song_id","message":"Song 2 is already in setlist"}]}"#,
+        );
+        let new_setlist_song = NewSetlistSong {
+            song_id: 2,
+            position: None,
+        };
+        let error = block_on(client.add_setlist_song(3, &new_setlist_song))
+            .err()
+            .unwrap();
+        assert!(error.has_code(ErrorCode::Validation));
+        assert_eq!(error.api_error().unwrap().fields[0].field, "song_id");
+
+        let client = self::client(502, "Bad Gateway");
+        let error = block_on(client.list_songs()).err().unwrap();
+        assert!(matches!(
+            error,
+            Error::UnexpectedResponse { status: 502, ref message } if message == "Bad Gateway"
+        ));
+        assert!(!error.has_code(ErrorCode::Internal));
+
+        let client = self::client(200, "not json");
+        let error = block_on(client.list_songs()).err().unwrap();
+        assert!(matches!(
+            error,
+            Error::UnexpectedResponse { status: 200, .. }
+        ));
+    }
+}
@@ -0,0 +1,42 @@
+use gloo_net::http::{self, RequestCredentials};
+
+use super::{Method, Request, Response, Transport};
+use crate::Error;
+
+/// Uses `fetch` of the browser. Credentials are included so the session cookie
+/// is sent along, the token of `Client` is not needed then.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct GlooTransport;
+
+impl Transport for GlooTransport {
+    async fn send(&self, request: Request) -> Result<Response, Error> {
+        let method = match request.method {
+            Method::Get => http::Method::GET,
+            Method::Post => http::Method::POST,
+            Method::Put => http::Method::PUT,
+            Method::Patch => http::Method::PATCH,
+            Method::Delete => http::Method::DELETE,
+        };
+        let mut gloo_request = http::Request::new(&request.url)
+            .method(method)
+            .credentials(RequestCredentials::Include);
+        if let Some(token) = &request.token {
+            gloo_request = gloo_request.header("Authorization", &format!("Bearer {}", token));
+        }
+        if let Some(body) = request.body {
+            gloo_request = gloo_request
+                .header("Content-Type", "application/json")
+                .body(body);
+        }
+
+        let response = gloo_request.send().await.map_err(transport_error)?;
+        Ok(Response {
+            status: response.status(),
+            body: response.binary().await.map_err(transport_error)?,
+        })
+    }
+}
+
+fn transport_error(e: gloo_net::Error) -> Error {
+    Error::Transport(e.to_string())
+}
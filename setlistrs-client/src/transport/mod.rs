@@ -0,0 +1,54 @@
+//! Sends requests built by `Client`. Transports for the browser and for native
+//! programs are behind `gloo` and `reqwest` features.
+
+use std::future::Future;
+
+use crate::Error;
+
+#[cfg(feature = "gloo")]
+mod gloo;
+#[cfg(feature = "reqwest")]
+mod reqwest;
+
+#[cfg(feature = "gloo")]
+pub use self::gloo::GlooTransport;
+#[cfg(feature = "reqwest")]
+pub use self::reqwest::ReqwestTransport;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub method: Method,
+    /// Absolute URL including query.
+    pub url: String,
+    /// Sent as `Authorization: Bearer <token>`.
+    pub token: Option<String>,
+    /// JSON body.
+    pub body: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Response {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn ok(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+pub trait Transport {
+    /// Responses with any status are `Ok`, `Err` is only for requests which
+    /// did not get any response, see `Error::Transport`.
+    fn send(&self, request: Request) -> impl Future<Output = Result<Response, Error>>;
+}
@@ -0,0 +1,54 @@
+use reqwest::header::CONTENT_TYPE;
+
+use super::{Method, Request, Response, Transport};
+use crate::Error;
+
+/// For native programs, log in with `Client::login` and pass the token of
+/// the session to `Client::with_token`.
+#[derive(Clone, Default, Debug)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl From<reqwest::Client> for ReqwestTransport {
+    fn from(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: Request) -> Result<Response, Error> {
+        let method = match request.method {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+            Method::Put => reqwest::Method::PUT,
+            Method::Patch => reqwest::Method::PATCH,
+            Method::Delete => reqwest::Method::DELETE,
+        };
+        let mut reqwest_request = self.client.request(method, &request.url);
+        if let Some(token) = &request.token {
+            reqwest_request = reqwest_request.bearer_auth(token);
+        }
+        if let Some(body) = request.body {
+            reqwest_request = reqwest_request
+                .header(CONTENT_TYPE, "application/json")
+                .body(body);
+        }
+
+        let response = reqwest_request.send().await.map_err(transport_error)?;
+        Ok(Response {
+            status: response.status().as_u16(),
+            body: response.bytes().await.map_err(transport_error)?.to_vec(),
+        })
+    }
+}
+
+fn transport_error(e: reqwest::Error) -> Error {
+    Error::Transport(e.to_string())
+}
@@ -0,0 +1,11 @@
+-- Add migration script here
+ALTER TABLE setlist_to_song_relations
+ADD position INT NOT NULL DEFAULT 0;
+
+UPDATE setlist_to_song_relations
+SET position = (
+  SELECT COUNT(*)
+  FROM setlist_to_song_relations previous
+  WHERE previous.setlist_id = setlist_to_song_relations.setlist_id
+  AND previous.id < setlist_to_song_relations.id
+);
@@ -0,0 +1,12 @@
+-- Add migration script here
+ALTER TABLE setlists
+ADD gig_date text DEFAULT NULL;
+
+ALTER TABLE setlists
+ADD start_time text DEFAULT NULL;
+
+ALTER TABLE setlists
+ADD venue text DEFAULT NULL;
+
+ALTER TABLE setlists
+ADD notes text DEFAULT NULL;
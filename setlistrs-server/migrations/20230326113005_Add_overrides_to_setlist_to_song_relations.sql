@@ -0,0 +1,15 @@
+-- Add migration script here
+ALTER TABLE setlist_to_song_relations
+ADD custom_chords text DEFAULT NULL;
+
+ALTER TABLE setlist_to_song_relations
+ADD custom_chordpro text DEFAULT NULL;
+
+ALTER TABLE setlist_to_song_relations
+ADD transpose INT NOT NULL DEFAULT 0;
+
+ALTER TABLE setlist_to_song_relations
+ADD capo INT DEFAULT NULL;
+
+ALTER TABLE setlist_to_song_relations
+ADD note text DEFAULT NULL;
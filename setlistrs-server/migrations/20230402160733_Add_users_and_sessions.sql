@@ -0,0 +1,15 @@
+-- Add migration script here
+CREATE TABLE users (
+  id integer PRIMARY KEY,
+  username text NOT NULL UNIQUE,
+  password_hash text NOT NULL,
+  created_at INT NOT NULL
+);
+
+CREATE TABLE sessions (
+  token text PRIMARY KEY,
+  user_id integer NOT NULL,
+  created_at INT NOT NULL,
+  expires_at INT NOT NULL,
+  FOREIGN KEY(user_id) REFERENCES users(id)
+);
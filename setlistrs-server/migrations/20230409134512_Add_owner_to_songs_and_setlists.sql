@@ -0,0 +1,21 @@
+-- Add migration script here
+ALTER TABLE songs
+ADD owner_id integer DEFAULT NULL REFERENCES users(id);
+
+ALTER TABLE setlists
+ADD owner_id integer DEFAULT NULL REFERENCES users(id);
+
+-- Content created before accounts existed is handed over to admin, who can
+-- login once SETLISTRS_ADMIN_PASSWORD is set for the server.
+INSERT INTO users(username, password_hash, created_at)
+SELECT 'admin', '', strftime('%s', 'now')
+WHERE NOT EXISTS (SELECT 1 FROM users WHERE username = 'admin')
+AND (EXISTS (SELECT 1 FROM songs) OR EXISTS (SELECT 1 FROM setlists));
+
+UPDATE songs
+SET owner_id = (SELECT id FROM users WHERE username = 'admin')
+WHERE owner_id IS NULL;
+
+UPDATE setlists
+SET owner_id = (SELECT id FROM users WHERE username = 'admin')
+WHERE owner_id IS NULL;
@@ -0,0 +1,35 @@
+-- Add migration script here
+CREATE TABLE bands (
+  id integer PRIMARY KEY,
+  name text NOT NULL,
+  created_at INT NOT NULL
+);
+
+CREATE TABLE band_members (
+  id integer PRIMARY KEY,
+  band_id integer NOT NULL,
+  user_id integer NOT NULL,
+  role text NOT NULL,
+  created_at INT NOT NULL,
+  UNIQUE(band_id, user_id),
+  FOREIGN KEY(band_id) REFERENCES bands(id),
+  FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+CREATE TABLE band_invites (
+  code text PRIMARY KEY,
+  band_id integer NOT NULL,
+  role text NOT NULL,
+  created_by integer NOT NULL,
+  created_at INT NOT NULL,
+  expires_at INT NOT NULL,
+  FOREIGN KEY(band_id) REFERENCES bands(id),
+  FOREIGN KEY(created_by) REFERENCES users(id)
+);
+
+-- Band content has no single owner, `owner_id` stays NULL for it.
+ALTER TABLE songs
+ADD band_id integer DEFAULT NULL REFERENCES bands(id);
+
+ALTER TABLE setlists
+ADD band_id integer DEFAULT NULL REFERENCES bands(id);
@@ -0,0 +1,10 @@
+-- Add migration script here
+CREATE TABLE setlist_shares (
+  token text PRIMARY KEY,
+  setlist_id integer NOT NULL,
+  created_by integer NOT NULL,
+  created_at INT NOT NULL,
+  expires_at INT DEFAULT NULL,
+  FOREIGN KEY(setlist_id) REFERENCES setlists(id),
+  FOREIGN KEY(created_by) REFERENCES users(id)
+);
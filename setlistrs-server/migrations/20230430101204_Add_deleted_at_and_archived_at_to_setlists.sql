@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE setlists
+ADD deleted_at INT DEFAULT NULL;
+
+ALTER TABLE setlists
+ADD archived_at INT DEFAULT NULL;
@@ -0,0 +1,96 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+use actix_web::{
+    web::{Data, Query},
+    FromRequest, HttpRequest,
+};
+use setlistrs_types::error::ApiError;
+use setlistrs_types::{BandQuery, Role};
+use sqlx::SqlitePool;
+
+use crate::band::repository;
+use crate::error::api_error;
+use crate::user::CurrentUser;
+
+/// Songs and setlists a request works with, either personal library of the
+/// logged in user or library of their band picked with `?band=<id>`.
+/// Requests for bands user is not member of are answered with 404.
+pub struct Library {
+    pub user_id: i64,
+    pub band_id: Option<i64>,
+    /// User is always owner of their personal library.
+    pub role: Role,
+}
+
+impl Library {
+    pub fn personal(user_id: i64) -> Self {
+        Self {
+            user_id,
+            band_id: None,
+            role: Role::Owner,
+        }
+    }
+
+    /// Owner of content in this library, band content has no single owner.
+    pub fn owner_id(&self) -> Option<i64> {
+        match self.band_id {
+            Some(_) => None,
+            None => Some(self.user_id),
+        }
+    }
+
+    pub fn ensure_role(&self, required: Role) -> Result<(), InsufficientRole> {
+        if self.role >= required {
+            Ok(())
+        } else {
+            Err(InsufficientRole { required })
+        }
+    }
+}
+
+impl FromRequest for Library {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let current_user = CurrentUser::from_request(req, payload);
+        let band_query = Query::<BandQuery>::from_query(req.query_string());
+        let pool = req.app_data::<Data<SqlitePool>>().cloned();
+
+        Box::pin(async move {
+            let user_id = current_user.await?.user.id;
+            let band_id = match band_query {
+                Ok(band_query) => band_query.band,
+                Err(e) => return Err(ApiError::bad_request(format!("Invalid band, {}", e)).into()),
+            };
+            let band_id = match band_id {
+                Some(band_id) => band_id,
+                None => return Ok(Library::personal(user_id)),
+            };
+            let pool = pool.ok_or_else(ApiError::internal)?;
+
+            match repository::library(pool.get_ref(), user_id, band_id).await {
+                Ok(Some(library)) => Ok(library),
+                Ok(None) => Err(ApiError::not_found("Band not found").into()),
+                Err(e) => Err(api_error(e).into()),
+            }
+        })
+    }
+}
+
+/// Returned by repositories when user's role in the band is too low for
+/// what they try to do.
+#[derive(Debug)]
+pub struct InsufficientRole {
+    pub required: Role,
+}
+
+impl fmt::Display for InsufficientRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "This needs at least {} role in the band", self.required)
+    }
+}
+
+impl std::error::Error for InsufficientRole {}
@@ -0,0 +1,6 @@
+mod library;
+mod repository;
+mod routes;
+
+pub use library::{InsufficientRole, Library};
+pub use routes::init;
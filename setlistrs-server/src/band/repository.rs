@@ -0,0 +1,594 @@
+use anyhow::{Error, Result};
+use chrono::Utc;
+use setlistrs_types::{Band, BandDetails, BandInvite, BandList, BandMember, Role, User};
+use sqlx::{query, Sqlite, SqlitePool, Transaction};
+
+use crate::band::Library;
+
+/// Library of the band for given user, `None` when they are not its member.
+pub async fn library(pool: &SqlitePool, user_id: i64, band_id: i64) -> Result<Option<Library>> {
+    let member = query!(
+        r#"
+SELECT role
+FROM band_members
+WHERE band_id = ?
+AND user_id = ?
+        "#,
+        band_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(match member {
+        Some(member) => Some(Library {
+            user_id,
+            band_id: Some(band_id),
+            role: member.role.parse().map_err(Error::msg)?,
+        }),
+        None => None,
+    })
+}
+
+/// Band routes only work with band libraries, see `routes`, personal one is
+/// an error.
+fn band_id(library: &Library) -> Result<i64> {
+    library
+        .band_id
+        .ok_or_else(|| Error::msg("Personal library is not a band"))
+}
+
+pub async fn find_all(pool: &SqlitePool, user_id: i64) -> Result<BandList> {
+    let bands = query!(
+        r#"
+SELECT b.id AS "id!", b.name, m.role
+FROM bands b, band_members m
+WHERE m.user_id = ?
+AND b.id = m.band_id
+ORDER BY b.name, b.id
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut data = Vec::with_capacity(bands.len());
+    for band in bands {
+        data.push(Band {
+            id: band.id,
+            name: band.name,
+            role: band.role.parse().map_err(Error::msg)?,
+        });
+    }
+
+    Ok(BandList { data })
+}
+
+pub async fn find_by_id(pool: &SqlitePool, library: &Library) -> Result<BandDetails> {
+    let band_id = band_id(library)?;
+    let band = query!(
+        r#"
+SELECT name
+FROM bands
+WHERE id = ?
+        "#,
+        band_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let rows = query!(
+        r#"
+SELECT u.id AS "id!", u.username, m.role
+FROM users u, band_members m
+WHERE m.band_id = ?
+AND u.id = m.user_id
+ORDER BY m.id
+        "#,
+        band_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut members = Vec::with_capacity(rows.len());
+    for row in rows {
+        members.push(BandMember {
+            user: User {
+                id: row.id,
+                username: row.username,
+            },
+            role: row.role.parse().map_err(Error::msg)?,
+        });
+    }
+
+    Ok(BandDetails {
+        band: Band {
+            id: band_id,
+            name: band.name,
+            role: library.role,
+        },
+        members,
+    })
+}
+
+/// User creating the band becomes its owner.
+pub async fn create(pool: &SqlitePool, user_id: i64, name: &str) -> Result<Band> {
+    let mut transaction = pool.begin().await?;
+    let created_at = Utc::now().timestamp();
+
+    let band_id = query!(
+        r#"
+INSERT INTO bands(name, created_at)
+VALUES (?, ?)
+        "#,
+        name,
+        created_at
+    )
+    .execute(&mut transaction)
+    .await?
+    .last_insert_rowid();
+
+    persist_member(&mut transaction, band_id, user_id, Role::Owner).await?;
+
+    transaction.commit().await?;
+
+    Ok(Band {
+        id: band_id,
+        name: name.to_string(),
+        role: Role::Owner,
+    })
+}
+
+pub async fn rename(pool: &SqlitePool, library: &Library, name: &str) -> Result<()> {
+    library.ensure_role(Role::Owner)?;
+    let band_id = band_id(library)?;
+
+    query!(
+        r#"
+UPDATE bands
+SET name = ?
+WHERE id = ?
+        "#,
+        name,
+        band_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+async fn persist_member(
+    transaction: &mut Transaction<'_, Sqlite>,
+    band_id: i64,
+    user_id: i64,
+    role: Role,
+) -> Result<i64> {
+    let role = role.as_str();
+    let created_at = Utc::now().timestamp();
+
+    Ok(query!(
+        r#"
+INSERT INTO band_members(band_id, user_id, role, created_at)
+VALUES (?, ?, ?, ?)
+        "#,
+        band_id,
+        user_id,
+        role,
+        created_at
+    )
+    .execute(transaction)
+    .await?
+    .last_insert_rowid())
+}
+
+pub async fn create_invite(
+    pool: &SqlitePool,
+    library: &Library,
+    code: &str,
+    role: Role,
+    expires_at: i64,
+) -> Result<BandInvite> {
+    library.ensure_role(Role::Owner)?;
+    let band_id = band_id(library)?;
+    let role_name = role.as_str();
+    let created_at = Utc::now().timestamp();
+
+    query!(
+        r#"
+INSERT INTO band_invites(code, band_id, role, created_by, created_at, expires_at)
+VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+        code,
+        band_id,
+        role_name,
+        library.user_id,
+        created_at,
+        expires_at
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(BandInvite {
+        code: code.to_string(),
+        role,
+        expires_at,
+    })
+}
+
+/// Invites which were not used and did not expire yet.
+pub async fn find_invites(pool: &SqlitePool, library: &Library) -> Result<Vec<BandInvite>> {
+    library.ensure_role(Role::Owner)?;
+    let band_id = band_id(library)?;
+    let now = Utc::now().timestamp();
+
+    let rows = query!(
+        r#"
+SELECT code AS "code!", role, expires_at
+FROM band_invites
+WHERE band_id = ?
+AND expires_at > ?
+ORDER BY created_at
+        "#,
+        band_id,
+        now
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut invites = Vec::with_capacity(rows.len());
+    for row in rows {
+        invites.push(BandInvite {
+            code: row.code,
+            role: row.role.parse().map_err(Error::msg)?,
+            expires_at: row.expires_at,
+        });
+    }
+
+    Ok(invites)
+}
+
+/// Returns `false` when band has no such invite.
+pub async fn delete_invite(pool: &SqlitePool, library: &Library, code: &str) -> Result<bool> {
+    library.ensure_role(Role::Owner)?;
+    let band_id = band_id(library)?;
+
+    Ok(query!(
+        r#"
+DELETE FROM band_invites
+WHERE code = ?
+AND band_id = ?
+        "#,
+        code,
+        band_id
+    )
+    .execute(pool)
+    .await?
+    .rows_affected()
+        > 0)
+}
+
+pub enum JoinOutcome {
+    Joined(Band),
+    InviteNotFound,
+    AlreadyMember,
+}
+
+/// Makes user member of the band the invite is for, invite is used up.
+pub async fn join(pool: &SqlitePool, user_id: i64, code: &str) -> Result<JoinOutcome> {
+    let mut transaction = pool.begin().await?;
+    let now = Utc::now().timestamp();
+
+    let invite = match query!(
+        r#"
+SELECT i.band_id, i.role, b.name
+FROM band_invites i, bands b
+WHERE i.code = ?
+AND i.expires_at > ?
+AND b.id = i.band_id
+        "#,
+        code,
+        now
+    )
+    .fetch_optional(&mut transaction)
+    .await?
+    {
+        Some(invite) => invite,
+        None => return Ok(JoinOutcome::InviteNotFound),
+    };
+
+    if query!(
+        r#"
+SELECT id
+FROM band_members
+WHERE band_id = ?
+AND user_id = ?
+        "#,
+        invite.band_id,
+        user_id
+    )
+    .fetch_optional(&mut transaction)
+    .await?
+    .is_some()
+    {
+        return Ok(JoinOutcome::AlreadyMember);
+    }
+
+    let role: Role = invite.role.parse().map_err(Error::msg)?;
+    persist_member(&mut transaction, invite.band_id, user_id, role).await?;
+
+    query!(
+        r#"
+DELETE FROM band_invites
+WHERE code = ?
+        "#,
+        code
+    )
+    .execute(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(JoinOutcome::Joined(Band {
+        id: invite.band_id,
+        name: invite.name,
+        role,
+    }))
+}
+
+pub enum MemberOutcome {
+    Done,
+    MemberNotFound,
+    /// Band would be left without anybody to manage it.
+    LastOwner,
+}
+
+pub async fn update_member_role(
+    pool: &SqlitePool,
+    library: &Library,
+    user_id: i64,
+    role: Role,
+) -> Result<MemberOutcome> {
+    library.ensure_role(Role::Owner)?;
+    let band_id = band_id(library)?;
+    let mut transaction = pool.begin().await?;
+
+    let current_role = match member_role(&mut transaction, band_id, user_id).await? {
+        Some(current_role) => current_role,
+        None => return Ok(MemberOutcome::MemberNotFound),
+    };
+    if current_role == Role::Owner
+        && role != Role::Owner
+        && owners_count(&mut transaction, band_id).await? == 1
+    {
+        return Ok(MemberOutcome::LastOwner);
+    }
+
+    let role = role.as_str();
+    query!(
+        r#"
+UPDATE band_members
+SET role = ?
+WHERE band_id = ?
+AND user_id = ?
+        "#,
+        role,
+        band_id,
+        user_id
+    )
+    .execute(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(MemberOutcome::Done)
+}
+
+/// Owners can remove anybody, other members can only leave on their own.
+pub async fn remove_member(
+    pool: &SqlitePool,
+    library: &Library,
+    user_id: i64,
+) -> Result<MemberOutcome> {
+    if user_id != library.user_id {
+        library.ensure_role(Role::Owner)?;
+    }
+    let band_id = band_id(library)?;
+    let mut transaction = pool.begin().await?;
+
+    let current_role = match member_role(&mut transaction, band_id, user_id).await? {
+        Some(current_role) => current_role,
+        None => return Ok(MemberOutcome::MemberNotFound),
+    };
+    if current_role == Role::Owner && owners_count(&mut transaction, band_id).await? == 1 {
+        return Ok(MemberOutcome::LastOwner);
+    }
+
+    query!(
+        r#"
+DELETE FROM band_members
+WHERE band_id = ?
+AND user_id = ?
+        "#,
+        band_id,
+        user_id
+    )
+    .execute(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(MemberOutcome::Done)
+}
+
+async fn member_role(
+    transaction: &mut Transaction<'_, Sqlite>,
+    band_id: i64,
+    user_id: i64,
+) -> Result<Option<Role>> {
+    match query!(
+        r#"
+SELECT role
+FROM band_members
+WHERE band_id = ?
+AND user_id = ?
+        "#,
+        band_id,
+        user_id
+    )
+    .fetch_optional(transaction)
+    .await?
+    {
+        Some(member) => Ok(Some(member.role.parse().map_err(Error::msg)?)),
+        None => Ok(None),
+    }
+}
+
+async fn owners_count(transaction: &mut Transaction<'_, Sqlite>, band_id: i64) -> Result<i64> {
+    let owner = Role::Owner.as_str();
+    Ok(query!(
+        r#"
+SELECT COUNT(*) AS owners_count
+FROM band_members
+WHERE band_id = ?
+AND role = ?
+        "#,
+        band_id,
+        owner
+    )
+    .fetch_one(transaction)
+    .await?
+    .owners_count as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::band::InsufficientRole;
+    use crate::test_util;
+
+    /// Band of `owner` with `members` joined through invites in given roles.
+    async fn band(pool: &SqlitePool, owner: i64, members: &[(i64, Role)]) -> i64 {
+        let band_id = create(pool, owner, "Band").await.unwrap().id;
+        let owner_library = library(pool, owner, band_id).await.unwrap().unwrap();
+        for (index, (user_id, role)) in members.iter().enumerate() {
+            let code = format!("code{}", index);
+            create_invite(pool, &owner_library, &code, *role, i64::MAX)
+                .await
+                .unwrap();
+            assert!(matches!(
+                join(pool, *user_id, &code).await.unwrap(),
+                JoinOutcome::Joined(_)
+            ));
+        }
+
+        band_id
+    }
+
+    async fn band_library(pool: &SqlitePool, user_id: i64, band_id: i64) -> Library {
+        library(pool, user_id, band_id).await.unwrap().unwrap()
+    }
+
+    fn is_insufficient_role(result: Result<impl Sized>) -> bool {
+        matches!(result, Err(e) if e.is::<InsufficientRole>())
+    }
+
+    #[actix_web::test]
+    async fn checks_role_of_member() {
+        let pool = test_util::pool().await;
+        let (ann, _) = test_util::user(&pool, "ann").await;
+        let (bob, _) = test_util::user(&pool, "bob").await;
+        let (cid, _) = test_util::user(&pool, "cid").await;
+        let band_id = band(&pool, ann, &[(bob, Role::Editor), (cid, Role::Viewer)]).await;
+
+        assert!(library(&pool, 99, band_id).await.unwrap().is_none());
+        for user_id in [bob, cid] {
+            let library = band_library(&pool, user_id, band_id).await;
+            assert!(is_insufficient_role(
+                rename(&pool, &library, "Renamed").await
+            ));
+            assert!(is_insufficient_role(find_invites(&pool, &library).await));
+            assert!(is_insufficient_role(
+                update_member_role(&pool, &library, ann, Role::Viewer).await
+            ));
+        }
+        let editor = band_library(&pool, bob, band_id).await;
+        assert!(is_insufficient_role(
+            remove_member(&pool, &editor, cid).await
+        ));
+
+        let owner = band_library(&pool, ann, band_id).await;
+        rename(&pool, &owner, "Renamed").await.unwrap();
+        assert!(matches!(
+            update_member_role(&pool, &owner, cid, Role::Editor)
+                .await
+                .unwrap(),
+            MemberOutcome::Done
+        ));
+        let details = find_by_id(&pool, &owner).await.unwrap();
+        assert_eq!(details.band.name, "Renamed");
+        let roles: Vec<Role> = details.members.iter().map(|member| member.role).collect();
+        assert_eq!(roles, [Role::Owner, Role::Editor, Role::Editor]);
+    }
+
+    #[actix_web::test]
+    async fn keeps_last_owner() {
+        let pool = test_util::pool().await;
+        let (ann, _) = test_util::user(&pool, "ann").await;
+        let (bob, _) = test_util::user(&pool, "bob").await;
+        let band_id = band(&pool, ann, &[(bob, Role::Editor)]).await;
+        let owner = band_library(&pool, ann, band_id).await;
+
+        assert!(matches!(
+            update_member_role(&pool, &owner, ann, Role::Editor)
+                .await
+                .unwrap(),
+            MemberOutcome::LastOwner
+        ));
+        assert!(matches!(
+            remove_member(&pool, &owner, ann).await.unwrap(),
+            MemberOutcome::LastOwner
+        ));
+
+        // With another owner the first one can leave.
+        update_member_role(&pool, &owner, bob, Role::Owner)
+            .await
+            .unwrap();
+        assert!(matches!(
+            remove_member(&pool, &owner, ann).await.unwrap(),
+            MemberOutcome::Done
+        ));
+        assert!(library(&pool, ann, band_id).await.unwrap().is_none());
+        assert!(matches!(
+            remove_member(&pool, &band_library(&pool, bob, band_id).await, ann)
+                .await
+                .unwrap(),
+            MemberOutcome::MemberNotFound
+        ));
+    }
+
+    #[actix_web::test]
+    async fn lets_members_leave_on_their_own() {
+        let pool = test_util::pool().await;
+        let (ann, _) = test_util::user(&pool, "ann").await;
+        let (bob, _) = test_util::user(&pool, "bob").await;
+        let band_id = band(&pool, ann, &[(bob, Role::Viewer)]).await;
+
+        let viewer = band_library(&pool, bob, band_id).await;
+        assert!(matches!(
+            remove_member(&pool, &viewer, bob).await.unwrap(),
+            MemberOutcome::Done
+        ));
+        assert!(library(&pool, bob, band_id).await.unwrap().is_none());
+    }
+
+    #[actix_web::test]
+    async fn rejects_personal_library() {
+        let pool = test_util::pool().await;
+        let (ann, _) = test_util::user(&pool, "ann").await;
+
+        assert!(rename(&pool, &Library::personal(ann), "Band")
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,249 @@
+use actix_web::{
+    delete, get, patch, post,
+    web::{Data, Json, Path, ServiceConfig},
+    HttpResponse, Responder, ResponseError,
+};
+use chrono::{Duration, Utc};
+use setlistrs_types::error::{ApiError, FieldError};
+use setlistrs_types::{BandInviteList, BandJoin, BandMemberPatch, NewBand, NewBandInvite};
+use sqlx::SqlitePool;
+
+use crate::band::repository::{self, JoinOutcome, MemberOutcome};
+use crate::band::Library;
+use crate::error::error_response;
+use crate::user::CurrentUser;
+
+const MAX_NAME_LENGTH: usize = 64;
+const INVITE_DAYS: i64 = 7;
+
+pub fn init(config: &mut ServiceConfig) {
+    config
+        .service(find_all)
+        .service(create)
+        .service(join)
+        .service(find_by_id)
+        .service(update)
+        .service(create_invite)
+        .service(find_invites)
+        .service(delete_invite)
+        .service(update_member)
+        .service(remove_member);
+}
+
+#[get("/bands")]
+async fn find_all(pool: Data<SqlitePool>, current_user: CurrentUser) -> impl Responder {
+    match repository::find_all(pool.get_ref(), current_user.user.id).await {
+        Ok(bands) => HttpResponse::Ok().json(bands),
+        Err(e) => error_response(e),
+    }
+}
+#[post("/bands")]
+async fn create(
+    pool: Data<SqlitePool>,
+    current_user: CurrentUser,
+    new_band: Json<NewBand>,
+) -> impl Responder {
+    let name = match validate_name(&new_band.name) {
+        Ok(name) => name,
+        Err(e) => return ApiError::validation(vec![e]).error_response(),
+    };
+    match repository::create(pool.get_ref(), current_user.user.id, name).await {
+        Ok(band) => HttpResponse::Created().json(band),
+        Err(e) => error_response(e),
+    }
+}
+#[post("/bands/join")]
+async fn join(
+    pool: Data<SqlitePool>,
+    current_user: CurrentUser,
+    band_join: Json<BandJoin>,
+) -> impl Responder {
+    match repository::join(pool.get_ref(), current_user.user.id, band_join.code.trim()).await {
+        Ok(JoinOutcome::Joined(band)) => HttpResponse::Ok().json(band),
+        Ok(JoinOutcome::InviteNotFound) => {
+            ApiError::not_found("Invite code is not valid or has expired").error_response()
+        }
+        Ok(JoinOutcome::AlreadyMember) => {
+            ApiError::conflict("You already are member of this band").error_response()
+        }
+        Err(e) => error_response(e),
+    }
+}
+#[get("/bands/{band_id}")]
+async fn find_by_id(
+    pool: Data<SqlitePool>,
+    current_user: CurrentUser,
+    band_id: Path<i64>,
+) -> impl Responder {
+    let library = match band_library(pool.get_ref(), &current_user, band_id.into_inner()).await {
+        Ok(library) => library,
+        Err(response) => return response,
+    };
+    match repository::find_by_id(pool.get_ref(), &library).await {
+        Ok(band) => HttpResponse::Ok().json(band),
+        Err(e) => error_response(e),
+    }
+}
+#[patch("/bands/{band_id}")]
+async fn update(
+    pool: Data<SqlitePool>,
+    current_user: CurrentUser,
+    band_id: Path<i64>,
+    band_patch: Json<NewBand>,
+) -> impl Responder {
+    let library = match band_library(pool.get_ref(), &current_user, band_id.into_inner()).await {
+        Ok(library) => library,
+        Err(response) => return response,
+    };
+    let name = match validate_name(&band_patch.name) {
+        Ok(name) => name,
+        Err(e) => return ApiError::validation(vec![e]).error_response(),
+    };
+    if let Err(e) = repository::rename(pool.get_ref(), &library, name).await {
+        return error_response(e);
+    }
+    match repository::find_by_id(pool.get_ref(), &library).await {
+        Ok(band) => HttpResponse::Ok().json(band),
+        Err(e) => error_response(e),
+    }
+}
+#[post("/bands/{band_id}/invites")]
+async fn create_invite(
+    pool: Data<SqlitePool>,
+    current_user: CurrentUser,
+    band_id: Path<i64>,
+    new_invite: Json<NewBandInvite>,
+) -> impl Responder {
+    let library = match band_library(pool.get_ref(), &current_user, band_id.into_inner()).await {
+        Ok(library) => library,
+        Err(response) => return response,
+    };
+    let expires_at = (Utc::now() + Duration::days(INVITE_DAYS)).timestamp();
+    match repository::create_invite(
+        pool.get_ref(),
+        &library,
+        &invite_code(),
+        new_invite.role,
+        expires_at,
+    )
+    .await
+    {
+        Ok(invite) => HttpResponse::Created().json(invite),
+        Err(e) => error_response(e),
+    }
+}
+#[get("/bands/{band_id}/invites")]
+async fn find_invites(
+    pool: Data<SqlitePool>,
+    current_user: CurrentUser,
+    band_id: Path<i64>,
+) -> impl Responder {
+    let library = match band_library(pool.get_ref(), &current_user, band_id.into_inner()).await {
+        Ok(library) => library,
+        Err(response) => return response,
+    };
+    match repository::find_invites(pool.get_ref(), &library).await {
+        Ok(invites) => HttpResponse::Ok().json(BandInviteList { data: invites }),
+        Err(e) => error_response(e),
+    }
+}
+#[delete("/bands/{band_id}/invites/{code}")]
+async fn delete_invite(
+    pool: Data<SqlitePool>,
+    current_user: CurrentUser,
+    path: Path<(i64, String)>,
+) -> impl Responder {
+    let (band_id, code) = path.into_inner();
+    let library = match band_library(pool.get_ref(), &current_user, band_id).await {
+        Ok(library) => library,
+        Err(response) => return response,
+    };
+    match repository::delete_invite(pool.get_ref(), &library, &code).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => ApiError::not_found("Invite not found").error_response(),
+        Err(e) => error_response(e),
+    }
+}
+#[patch("/bands/{band_id}/members/{user_id}")]
+async fn update_member(
+    pool: Data<SqlitePool>,
+    current_user: CurrentUser,
+    path: Path<(i64, i64)>,
+    member_patch: Json<BandMemberPatch>,
+) -> impl Responder {
+    let (band_id, user_id) = path.into_inner();
+    let library = match band_library(pool.get_ref(), &current_user, band_id).await {
+        Ok(library) => library,
+        Err(response) => return response,
+    };
+    match repository::update_member_role(pool.get_ref(), &library, user_id, member_patch.role).await
+    {
+        Ok(MemberOutcome::Done) => match repository::find_by_id(pool.get_ref(), &library).await {
+            Ok(band) => HttpResponse::Ok().json(band),
+            Err(e) => error_response(e),
+        },
+        Ok(MemberOutcome::MemberNotFound) => member_not_found(),
+        Ok(MemberOutcome::LastOwner) => last_owner(),
+        Err(e) => error_response(e),
+    }
+}
+#[delete("/bands/{band_id}/members/{user_id}")]
+async fn remove_member(
+    pool: Data<SqlitePool>,
+    current_user: CurrentUser,
+    path: Path<(i64, i64)>,
+) -> impl Responder {
+    let (band_id, user_id) = path.into_inner();
+    let library = match band_library(pool.get_ref(), &current_user, band_id).await {
+        Ok(library) => library,
+        Err(response) => return response,
+    };
+    match repository::remove_member(pool.get_ref(), &library, user_id).await {
+        Ok(MemberOutcome::Done) => HttpResponse::NoContent().finish(),
+        Ok(MemberOutcome::MemberNotFound) => member_not_found(),
+        Ok(MemberOutcome::LastOwner) => last_owner(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Library of the band from path, bands user is not member of are not found.
+async fn band_library(
+    pool: &SqlitePool,
+    current_user: &CurrentUser,
+    band_id: i64,
+) -> Result<Library, HttpResponse> {
+    match repository::library(pool, current_user.user.id, band_id).await {
+        Ok(Some(library)) => Ok(library),
+        Ok(None) => Err(ApiError::not_found("Band not found").error_response()),
+        Err(e) => Err(error_response(e)),
+    }
+}
+
+fn validate_name(name: &str) -> Result<&str, FieldError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(FieldError::new(
+            "name",
+            format!(
+                "Band name has to have between 1 and {} characters",
+                MAX_NAME_LENGTH
+            ),
+        ));
+    }
+
+    Ok(name)
+}
+
+/// Random 48 bit code, hex encoded, short enough to be sent in a message.
+fn invite_code() -> String {
+    let bytes: [u8; 6] = rand::random();
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn last_owner() -> HttpResponse {
+    ApiError::conflict("Band has to keep at least one owner").error_response()
+}
+
+fn member_not_found() -> HttpResponse {
+    ApiError::not_found("Member not found").error_response()
+}
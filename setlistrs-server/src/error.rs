@@ -0,0 +1,213 @@
+//! Turns errors returned by repositories into `ApiError` responses.
+
+use actix_web::{
+    error::{JsonPayloadError, QueryPayloadError},
+    web::{JsonConfig, QueryConfig},
+    HttpRequest, HttpResponse, ResponseError,
+};
+use setlistrs_types::chordpro;
+use setlistrs_types::error::{ApiError, FieldError};
+
+use crate::band::InsufficientRole;
+
+pub fn api_error(e: anyhow::Error) -> ApiError {
+    if let Some(parse_error) = e.downcast_ref::<chordpro::ParseError>() {
+        return ApiError::validation(vec![FieldError::new(
+            "chordpro",
+            format!("Invalid ChordPro, {}", parse_error),
+        )]);
+    }
+    if let Some(insufficient_role) = e.downcast_ref::<InsufficientRole>() {
+        return ApiError::forbidden(insufficient_role.to_string());
+    }
+
+    match e.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::RowNotFound) => ApiError::not_found("Not found"),
+        Some(sqlx::Error::Database(database_error))
+            if database_error
+                .message()
+                .contains("UNIQUE constraint failed") =>
+        {
+            ApiError::conflict("Already exists")
+        }
+        _ => {
+            // Client gets generic message only, details are kept in server log.
+            log::error!("Request failed: {:?}", e);
+            ApiError::internal()
+        }
+    }
+}
+
+pub fn error_response(e: anyhow::Error) -> HttpResponse {
+    api_error(e).error_response()
+}
+
+/// Malformed JSON bodies are answered with `ApiError` as well.
+pub fn json_config() -> JsonConfig {
+    JsonConfig::default().error_handler(|e: JsonPayloadError, _: &HttpRequest| {
+        ApiError::bad_request(e.to_string()).into()
+    })
+}
+
+pub fn query_config() -> QueryConfig {
+    QueryConfig::default().error_handler(|e: QueryPayloadError, _: &HttpRequest| {
+        ApiError::bad_request(e.to_string()).into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use setlistrs_types::error::ErrorCode;
+    use setlistrs_types::{Credentials, NewSetlist, NewSetlistSong, Role, Song};
+
+    use super::*;
+    use crate::test_util::{self, bearer};
+
+    #[actix_web::test]
+    async fn maps_repository_errors() {
+        assert_eq!(
+            api_error(sqlx::Error::RowNotFound.into()).code,
+            ErrorCode::NotFound
+        );
+
+        let pool = test_util::pool().await;
+        let insert_ann =
+            "INSERT INTO users(username, password_hash, created_at) VALUES('ann', '', 0)";
+        sqlx::query(insert_ann).execute(&pool).await.unwrap();
+        let unique_violation = sqlx::query(insert_ann).execute(&pool).await.unwrap_err();
+        assert_eq!(api_error(unique_violation.into()).code, ErrorCode::Conflict);
+
+        let parse_error = chordpro::parse("[G").unwrap_err();
+        let validation = api_error(parse_error.into());
+        assert_eq!(validation.code, ErrorCode::Validation);
+        assert_eq!(validation.fields[0].field, "chordpro");
+
+        let insufficient_role = InsufficientRole {
+            required: Role::Editor,
+        };
+        assert_eq!(
+            api_error(insufficient_role.into()).code,
+            ErrorCode::Forbidden
+        );
+
+        let other = api_error(anyhow::anyhow!("disk is full"));
+        assert_eq!(other.code, ErrorCode::Internal);
+        assert!(!other.message.contains("disk"));
+    }
+
+    #[actix_web::test]
+    async fn responds_with_api_errors() {
+        let pool = test_util::pool().await;
+        let (_, token) = test_util::user(&pool, "ann").await;
+        let app = test::init_service(test_util::app(&pool)).await;
+
+        let response = test::call_service(
+            &app,
+            TestRequest::get()
+                .uri("/setlists/1")
+                .insert_header(bearer(&token))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body: ApiError = test::read_body_json(response).await;
+        assert_eq!(body.code, ErrorCode::NotFound);
+
+        let credentials = Credentials {
+            username: "ann".to_string(),
+            password: "long enough".to_string(),
+        };
+        let response = test::call_service(
+            &app,
+            TestRequest::post()
+                .uri("/register")
+                .set_json(&credentials)
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let song = Song {
+            name: "Song".to_string(),
+            source: Vec::new(),
+            cover: None,
+            chords: "G".to_string(),
+            chordpro: None,
+        };
+        let response = test::call_service(
+            &app,
+            TestRequest::post()
+                .uri("/songs")
+                .insert_header(bearer(&token))
+                .set_json(&song)
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let response = test::call_service(
+            &app,
+            TestRequest::post()
+                .uri("/setlists")
+                .insert_header(bearer(&token))
+                .set_json(NewSetlist {
+                    display_title: "Gig".to_string(),
+                    gig: Default::default(),
+                    songs: vec![1],
+                })
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let response = test::call_service(
+            &app,
+            TestRequest::post()
+                .uri("/setlists/1/songs")
+                .insert_header(bearer(&token))
+                .set_json(NewSetlistSong {
+                    song_id: 1,
+                    position: None,
+                })
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: ApiError = test::read_body_json(response).await;
+        assert_eq!(body.fields[0].field, "song_id");
+
+        let response = test::call_service(
+            &app,
+            TestRequest::post()
+                .uri("/setlists")
+                .insert_header(bearer(&token))
+                .insert_header(("Content-Type", "application/json"))
+                .set_payload("{")
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn lists_setlists_without_title() {
+        let pool = test_util::pool().await;
+        let (user_id, token) = test_util::user(&pool, "ann").await;
+        sqlx::query("INSERT INTO setlists(display_title, owner_id) VALUES(NULL, ?)")
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let app = test::init_service(test_util::app(&pool)).await;
+
+        let response = test::call_service(
+            &app,
+            TestRequest::get()
+                .uri("/setlists")
+                .insert_header(bearer(&token))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,36 @@
+//! Writes `log` records to stderr. Level is read from `SETLISTRS_LOG`, e.g.
+//! `debug`, and defaults to `info`. Statements logged by sqlx are shown only
+//! from `debug` up, they would drown everything else.
+
+use std::env;
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+struct StderrLogger;
+
+static LOGGER: StderrLogger = StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+            && (metadata.target() != "sqlx::query" || log::max_level() >= LevelFilter::Debug)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+pub fn init() {
+    let level = env::var("SETLISTRS_LOG")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(LevelFilter::Info);
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
@@ -6,16 +6,35 @@ use actix_web::web::Data;
 use actix_web::{App, HttpServer};
 use dotenvy::dotenv;
 
+mod band;
+mod error;
+mod logger;
+mod pdf;
 mod setlist;
 mod song;
+mod songbook;
+#[cfg(test)]
+mod test_util;
+mod user;
 
 #[actix_web::main]
 async fn main() -> anyhow::Result<()> {
     dotenv().ok();
+    logger::init();
 
     let dsn = env::var("DATABASE_URL").expect("DATABASE_URL has to be set");
     let pool = sqlx::SqlitePool::connect(&dsn).await?;
 
+    // Admin owns content created before user accounts existed and has no
+    // password until one is given here.
+    if let Ok(admin_password) = env::var("SETLISTRS_ADMIN_PASSWORD") {
+        if !user::set_password(&pool, "admin", &admin_password).await? {
+            log::warn!("SETLISTRS_ADMIN_PASSWORD is set, but there is no admin user");
+        }
+    } else if user::lacks_password(&pool, "admin").await? {
+        log::warn!("admin has no password and can not login, set SETLISTRS_ADMIN_PASSWORD");
+    }
+
     let host = match env::var("SETLISTRS_HOST") {
         Ok(host) => host,
         Err(_) => "0.0.0.0".into(),
@@ -30,9 +49,13 @@ async fn main() -> anyhow::Result<()> {
         let cors = Cors::permissive(); // TODO -> setup cors proper way
         App::new()
             .app_data(Data::new(pool.clone()))
+            .app_data(error::json_config())
+            .app_data(error::query_config())
             .wrap(cors)
             .configure(song::init)
             .configure(setlist::init)
+            .configure(user::init)
+            .configure(band::init)
     })
     .bind((host, port))?
     .run()
@@ -0,0 +1,371 @@
+//! Minimal PDF layout on top of `pdf-writer`, using standard Helvetica fonts
+//! so nothing has to be embedded. Text is flowed top to bottom on A4 pages,
+//! a new page is started whenever the current one is full.
+
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const FOOTER_SIZE: f32 = 9.0;
+const LINE_SPACING: f32 = 1.3;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+    /// Courier, used where characters have to stay aligned, e.g. in tabs.
+    Mono,
+}
+
+impl Font {
+    fn name(self) -> Name<'static> {
+        match self {
+            Font::Regular => Name(b"F1"),
+            Font::Bold => Name(b"F2"),
+            Font::Mono => Name(b"F3"),
+        }
+    }
+
+    /// Width of the text in points, see [`encode`] for supported characters.
+    pub fn width(self, text: &str, size: f32) -> f32 {
+        let units: u32 = encode(text)
+            .iter()
+            .map(|byte| match (self, *byte) {
+                (Font::Regular, 32..=126) => HELVETICA_WIDTHS[(*byte - 32) as usize] as u32,
+                (Font::Bold, 32..=126) => HELVETICA_BOLD_WIDTHS[(*byte - 32) as usize] as u32,
+                (Font::Mono, _) => 600,
+                _ => 556,
+            })
+            .sum();
+
+        units as f32 * size / 1000.0
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+}
+
+/// Piece of text placed on a line, `x` is relative to the left margin.
+pub struct Span<'a> {
+    pub x: f32,
+    pub text: &'a str,
+    pub font: Font,
+    pub size: f32,
+}
+
+pub struct Document {
+    title: String,
+    pages: Vec<Content>,
+    /// Baseline of the next line on the current page.
+    cursor: f32,
+}
+
+impl Document {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            pages: Vec::new(),
+            cursor: 0.0,
+        }
+    }
+
+    pub const fn content_width() -> f32 {
+        PAGE_WIDTH - 2.0 * MARGIN
+    }
+
+    pub const fn content_height() -> f32 {
+        PAGE_HEIGHT - 2.0 * MARGIN
+    }
+
+    pub fn new_page(&mut self) {
+        self.pages.push(Content::new());
+        self.cursor = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Number of pages started so far.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Height a line of text in given size takes.
+    pub fn line_height(size: f32) -> f32 {
+        size * LINE_SPACING
+    }
+
+    pub fn space(&mut self, height: f32) {
+        self.cursor -= height;
+    }
+
+    /// Writes text wrapped to the page width.
+    pub fn paragraph(&mut self, text: &str, font: Font, size: f32, align: Align) {
+        for line in wrap(text, font, size, Self::content_width()) {
+            let x = match align {
+                Align::Left => 0.0,
+                Align::Center => (Self::content_width() - font.width(&line, size)) / 2.0,
+            };
+            self.line(&[Span {
+                x,
+                text: &line,
+                font,
+                size,
+            }]);
+        }
+    }
+
+    /// Writes spans on a single line, it is not wrapped.
+    pub fn line(&mut self, spans: &[Span]) {
+        let size = spans.iter().map(|span| span.size).fold(0.0, f32::max);
+        self.ensure_space(size * LINE_SPACING);
+        self.cursor -= size;
+
+        let page = self.pages.last_mut().expect("Page was ensured above");
+        page.begin_text();
+        for span in spans {
+            page.set_font(span.font.name(), span.size);
+            page.set_text_matrix([1.0, 0.0, 0.0, 1.0, MARGIN + span.x, self.cursor]);
+            page.show(Str(&encode(span.text)));
+        }
+        page.end_text();
+
+        self.cursor -= size * (LINE_SPACING - 1.0);
+    }
+
+    /// Writes text and page number on the same line with dots in between,
+    /// as in table of contents.
+    pub fn leader_line(&mut self, text: &str, page_number: usize, font: Font, size: f32) {
+        let number = page_number.to_string();
+        let number_x = Self::content_width() - font.width(&number, size);
+        let dot_width = font.width(".", size);
+        let dots_count =
+            ((number_x - font.width(text, size) - 2.0 * dot_width) / dot_width).max(0.0) as usize;
+
+        self.line(&[
+            Span {
+                x: 0.0,
+                text,
+                font,
+                size,
+            },
+            Span {
+                x: number_x - dot_width * (dots_count as f32 + 1.0),
+                text: &".".repeat(dots_count),
+                font,
+                size,
+            },
+            Span {
+                x: number_x,
+                text: &number,
+                font,
+                size,
+            },
+        ]);
+    }
+
+    /// Starts a new page unless `height` still fits on the current one, used
+    /// to keep lines which belong together on the same page.
+    pub fn ensure_space(&mut self, height: f32) {
+        if self.pages.is_empty() || self.cursor - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    /// Renders the document, every page gets `page / pages` in its footer
+    /// when `page_numbers` is set.
+    pub fn finish(mut self, page_numbers: bool) -> Vec<u8> {
+        if self.pages.is_empty() {
+            self.new_page();
+        }
+
+        let catalog_id = Ref::new(1);
+        let page_tree_id = Ref::new(2);
+        let regular_font_id = Ref::new(3);
+        let bold_font_id = Ref::new(4);
+        let mono_font_id = Ref::new(5);
+        let info_id = Ref::new(6);
+        let first_page_id = 7;
+
+        let pages_count = self.pages.len();
+        let page_ids: Vec<Ref> = (0..pages_count)
+            .map(|index| Ref::new(first_page_id + 2 * index as i32))
+            .collect();
+
+        let mut pdf = Pdf::new();
+        pdf.catalog(catalog_id).pages(page_tree_id);
+        pdf.pages(page_tree_id)
+            .kids(page_ids.iter().copied())
+            .count(pages_count as i32);
+        pdf.type1_font(regular_font_id)
+            .base_font(Name(b"Helvetica"))
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+        pdf.type1_font(bold_font_id)
+            .base_font(Name(b"Helvetica-Bold"))
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+        pdf.type1_font(mono_font_id)
+            .base_font(Name(b"Courier"))
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+        pdf.document_info(info_id)
+            .title(TextStr(&self.title))
+            .producer(TextStr("setlistrs"));
+
+        for (index, (mut content, page_id)) in self.pages.into_iter().zip(page_ids).enumerate() {
+            if page_numbers {
+                let footer = format!("{} / {}", index + 1, pages_count);
+                let x = (PAGE_WIDTH - Font::Regular.width(&footer, FOOTER_SIZE)) / 2.0;
+                content
+                    .begin_text()
+                    .set_font(Font::Regular.name(), FOOTER_SIZE)
+                    .set_text_matrix([1.0, 0.0, 0.0, 1.0, x, MARGIN / 2.0])
+                    .show(Str(&encode(&footer)))
+                    .end_text();
+            }
+
+            let content_id = Ref::new(page_id.get() + 1);
+            let mut page = pdf.page(page_id);
+            page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+                .parent(page_tree_id)
+                .contents(content_id);
+            page.resources()
+                .fonts()
+                .pair(Font::Regular.name(), regular_font_id)
+                .pair(Font::Bold.name(), bold_font_id)
+                .pair(Font::Mono.name(), mono_font_id);
+            page.finish();
+
+            pdf.stream(content_id, &content.finish());
+        }
+
+        pdf.finish()
+    }
+}
+
+/// Splits text into lines fitting into `width`, words longer than a line are
+/// put on a line of their own.
+pub fn wrap(text: &str, font: Font, size: f32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+            if font.width(&candidate, size) > width && !line.is_empty() {
+                lines.push(line);
+                line = word.to_string();
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Encodes text as WinAnsi, which standard fonts use. Latin-1 characters are
+/// kept, common Central European letters lose their diacritics and anything
+/// else is replaced with `?`.
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            ' '..='~' | '\u{a0}'..='\u{ff}' => c as u8,
+            '\t' => b' ',
+            'ą' | 'ă' => b'a',
+            'Ą' | 'Ă' => b'A',
+            'ć' | 'č' => b'c',
+            'Ć' | 'Č' => b'C',
+            'ę' | 'ě' => b'e',
+            'Ę' | 'Ě' => b'E',
+            'ł' => b'l',
+            'Ł' => b'L',
+            'ń' | 'ň' => b'n',
+            'Ń' | 'Ň' => b'N',
+            'ř' => b'r',
+            'Ř' => b'R',
+            'ś' | 'š' | 'ș' => b's',
+            'Ś' | 'Š' | 'Ș' => b'S',
+            'ť' | 'ț' => b't',
+            'Ť' | 'Ț' => b'T',
+            'ů' | 'ű' => b'u',
+            'Ů' | 'Ű' => b'U',
+            'ź' | 'ż' | 'ž' => b'z',
+            'Ź' | 'Ż' | 'Ž' => b'Z',
+            'ő' => b'o',
+            'Ő' => b'O',
+            '‘' | '’' => b'\'',
+            '“' | '”' | '„' => b'"',
+            '–' | '—' => b'-',
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Glyph widths of characters 32 to 126 in 1/1000 of font size.
+#[rustfmt::skip]
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+#[rustfmt::skip]
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::pdf_pages;
+
+    #[test]
+    fn wraps_text_to_width() {
+        let lines = wrap(
+            "Fly me to the moon and let me play among the stars\n\nEnd",
+            Font::Regular,
+            12.0,
+            100.0,
+        );
+        assert!(lines.len() > 3);
+        assert!(lines
+            .iter()
+            .all(|line| Font::Regular.width(line, 12.0) <= 100.0 || !line.contains(' ')));
+        assert_eq!(lines.last().map(String::as_str), Some("End"));
+    }
+
+    #[test]
+    fn breaks_pages_when_full() {
+        let mut document = Document::new("Long");
+        for index in 0..200 {
+            document.paragraph(
+                &format!("Line {} with Žluťoučký kůň ♯", index),
+                Font::Regular,
+                12.0,
+                Align::Left,
+            );
+        }
+        let pages = document.page_count();
+        assert!(pages > 1);
+
+        let pdf = document.finish(true);
+        assert_eq!(pdf_pages(&pdf), pages);
+    }
+
+    #[test]
+    fn renders_empty_document_as_one_page() {
+        assert_eq!(pdf_pages(&Document::new("Empty").finish(false)), 1);
+    }
+}
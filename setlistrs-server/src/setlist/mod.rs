@@ -1,4 +1,6 @@
+mod pdf;
 mod repository;
 mod routes;
 
+pub use repository::renumber_songs;
 pub use routes::init;
@@ -0,0 +1,180 @@
+use setlistrs_types::{Setlist, SetlistSong};
+
+use crate::pdf::{Align, Document, Font, Span};
+use crate::songbook::{self, SongbookSong};
+
+const TITLE_SIZE: f32 = 24.0;
+const GIG_SIZE: f32 = 14.0;
+const MIN_SONG_SIZE: f32 = 14.0;
+const MAX_SONG_SIZE: f32 = 36.0;
+
+/// Stage sheet with song titles in order, set as big as still fits on one
+/// page. With `with_chords` every song gets a page with its chords after it.
+pub fn export(setlist: &Setlist, with_chords: bool) -> Vec<u8> {
+    let mut document = Document::new(&setlist.display_title);
+
+    document.paragraph(
+        &setlist.display_title,
+        Font::Bold,
+        TITLE_SIZE,
+        Align::Center,
+    );
+    let gig = [
+        setlist.gig.gig_date.as_deref(),
+        setlist.gig.start_time.as_deref(),
+        setlist.gig.venue.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<&str>>()
+    .join(" · ");
+    if !gig.is_empty() {
+        document.paragraph(&gig, Font::Regular, GIG_SIZE, Align::Center);
+    }
+    document.space(GIG_SIZE);
+
+    let size = song_size(setlist, &gig);
+    let numbers_width = Font::Regular.width(&format!("{}. ", setlist.songs.len()), size);
+    for (index, (_, song)) in setlist.songs.iter().enumerate() {
+        document.line(&[
+            Span {
+                x: 0.0,
+                text: &format!("{}.", index + 1),
+                font: Font::Regular,
+                size,
+            },
+            Span {
+                x: numbers_width,
+                text: &song.display_title,
+                font: Font::Bold,
+                size,
+            },
+        ]);
+    }
+
+    if with_chords {
+        for (_, song) in setlist.songs.iter() {
+            chords_page(&mut document, song);
+        }
+    }
+
+    document.finish(with_chords)
+}
+
+/// Font size of song titles, limited by page height and by the longest title.
+fn song_size(setlist: &Setlist, gig: &str) -> f32 {
+    let songs_count = setlist.songs.len().max(1) as f32;
+    let header_height =
+        TITLE_SIZE * 1.3 + if gig.is_empty() { 0.0 } else { GIG_SIZE * 1.3 } + GIG_SIZE;
+    let by_height = (Document::content_height() - header_height) / (songs_count * 1.3);
+
+    let numbers_width = Font::Regular.width(&format!("{}. ", setlist.songs.len()), 1.0);
+    let longest_title = setlist
+        .songs
+        .iter()
+        .map(|(_, song)| Font::Bold.width(&song.display_title, 1.0))
+        .fold(0.0, f32::max);
+    let by_width = Document::content_width() / (numbers_width + longest_title);
+
+    by_height.min(by_width).clamp(MIN_SONG_SIZE, MAX_SONG_SIZE)
+}
+
+fn chords_page(document: &mut Document, song: &SetlistSong) {
+    document.new_page();
+    document.paragraph(&song.display_title, Font::Bold, TITLE_SIZE, Align::Left);
+
+    if let Some(adjustments) = adjustments(song) {
+        document.paragraph(&adjustments, Font::Regular, GIG_SIZE, Align::Left);
+    }
+    document.space(GIG_SIZE);
+
+    document.paragraph(&song.chords, Font::Bold, MAX_SONG_SIZE, Align::Left);
+}
+
+/// Songbook with lyrics and chords of every song, in setlist order.
+pub fn songbook(setlist: &Setlist) -> Vec<u8> {
+    let songs: Vec<SongbookSong> = setlist
+        .songs
+        .iter()
+        .map(|(_, song)| SongbookSong {
+            title: &song.display_title,
+            details: adjustments(song),
+            chords: &song.chords,
+            chordpro: song.chordpro.as_deref(),
+        })
+        .collect();
+
+    songbook::render(&setlist.display_title, &songs)
+}
+
+/// Setlist specific transposition, capo and note of the song.
+fn adjustments(song: &SetlistSong) -> Option<String> {
+    let overrides = &song.overrides;
+    let adjustments = [
+        (overrides.transpose != 0).then(|| format!("Transposed {:+}", overrides.transpose)),
+        overrides.capo.map(|capo| format!("Capo {}", capo)),
+        overrides.note.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<String>>();
+
+    (!adjustments.is_empty()).then(|| adjustments.join(" · "))
+}
+
+#[cfg(test)]
+mod tests {
+    use setlistrs_types::{GigDetails, SetlistSongOverrides};
+
+    use super::*;
+    use crate::test_util::pdf_pages;
+
+    fn setlist(songs_count: usize) -> Setlist {
+        Setlist {
+            display_title: "Friday".to_string(),
+            gig: GigDetails {
+                gig_date: Some("2023-04-14".to_string()),
+                start_time: Some("20:00".to_string()),
+                venue: Some("Lucerna".to_string()),
+                notes: None,
+            },
+            songs: (0..songs_count)
+                .map(|index| {
+                    let song = SetlistSong {
+                        display_title: format!("Song number {}", index + 1),
+                        chords: "Verse: G D Em C\nChorus: C G D G".to_string(),
+                        chordpro: None,
+                        overrides: SetlistSongOverrides {
+                            capo: Some(2),
+                            ..Default::default()
+                        },
+                        deleted: false,
+                    };
+                    (index as i64 + 1, song)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn exports_stage_sheet_on_one_page() {
+        assert_eq!(pdf_pages(&export(&setlist(12), false)), 1);
+        assert_eq!(pdf_pages(&export(&setlist(0), false)), 1);
+    }
+
+    #[test]
+    fn continues_long_stage_sheet_on_next_page() {
+        assert!(pdf_pages(&export(&setlist(80), false)) > 1);
+    }
+
+    #[test]
+    fn exports_chords_page_per_song() {
+        assert_eq!(pdf_pages(&export(&setlist(3), true)), 4);
+    }
+
+    #[test]
+    fn exports_songbook_of_empty_setlist() {
+        assert_eq!(pdf_pages(&songbook(&setlist(0))), 1);
+        assert_eq!(pdf_pages(&songbook(&setlist(2))), 3);
+    }
+}
@@ -1,21 +1,43 @@
 use anyhow::Result;
-use setlistrs_types::{NewSetlist, Setlist, SetlistList, SetlistSong};
+use chrono::Utc;
+use setlistrs_types::{
+    chordpro, GigDetails, NewSetlist, Role, Setlist, SetlistList, SetlistPatch, SetlistShare,
+    SetlistSong, SetlistSongOverrides, SetlistSongOverridesPatch, SetlistSummary,
+};
 use sqlx::{query, Sqlite, SqlitePool, Transaction};
 
-pub async fn find_all(pool: &SqlitePool) -> Result<SetlistList> {
+use crate::band::Library;
+
+/// Either active or archived setlists, deleted ones are never listed.
+pub async fn find_all(pool: &SqlitePool, library: &Library, archived: bool) -> Result<SetlistList> {
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
     Ok(SetlistList {
         data: query!(
             r#"
-SELECT s.id, s.display_title FROM setlists s
+SELECT s.id, s.display_title, s.gig_date, s.start_time, s.venue, s.notes
+FROM setlists s
+WHERE s.owner_id IS ?
+AND s.band_id IS ?
+AND s.deleted_at IS NULL
+AND (s.archived_at IS NOT NULL) = ?
 ORDER BY s.id
             "#,
+            owner_id,
+            band_id,
+            archived
         )
         .map(|setlist| {
             (
                 setlist.id,
-                match setlist.display_title {
-                    Some(display_title) => display_title,
-                    None => panic!(),
+                SetlistSummary {
+                    display_title: setlist.display_title.unwrap_or_default(),
+                    gig: GigDetails {
+                        gig_date: setlist.gig_date,
+                        start_time: setlist.start_time,
+                        venue: setlist.venue,
+                        notes: setlist.notes,
+                    },
                 },
             )
         })
@@ -24,116 +46,770 @@ ORDER BY s.id
     })
 }
 
-pub async fn find_by_id(pool: &SqlitePool, setlist_id: i64) -> Result<Setlist> {
-    let setlist_display_title: String = query!(
+pub async fn find_by_id(pool: &SqlitePool, library: &Library, setlist_id: i64) -> Result<Setlist> {
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    let setlist = query!(
         r#"
-SELECT display_title
+SELECT display_title, gig_date, start_time, venue, notes
 FROM setlists
 WHERE id = ?
+AND owner_id IS ?
+AND band_id IS ?
+AND deleted_at IS NULL
         "#,
-        setlist_id
+        setlist_id,
+        owner_id,
+        band_id
     )
-    .map(|setlist| match setlist.display_title {
-        Some(display_title) => display_title,
-        None => panic!(),
+    .map(|setlist| SetlistSummary {
+        display_title: setlist.display_title.unwrap_or_default(),
+        gig: GigDetails {
+            gig_date: setlist.gig_date,
+            start_time: setlist.start_time,
+            venue: setlist.venue,
+            notes: setlist.notes,
+        },
     })
     .fetch_one(pool)
     .await?;
 
-    let songs: Vec<(i64, SetlistSong)> = match query!(
+    let songs: Vec<(i64, SetlistSong)> = query!(
         r#"
-SELECT s.id, s.name, s.chords
+SELECT s.id AS "id!", s.name, s.chords, s.chordpro, s.deleted_at,
+stsr.custom_chords, stsr.custom_chordpro, stsr.transpose, stsr.capo, stsr.note
 FROM songs s, setlist_to_song_relations stsr
 WHERE stsr.setlist_id = ?
 AND s.id = stsr.song_id
+ORDER BY stsr.position, stsr.id
                   "#,
         setlist_id
     )
     .map(|song| {
-        (
-            song.id,
-            SetlistSong {
-                display_title: song.name.unwrap(),
-                chords: song.chords.unwrap(),
-            },
-        )
+        let overrides = SetlistSongOverrides {
+            chords: song.custom_chords,
+            chordpro: song.custom_chordpro,
+            transpose: song.transpose as i32,
+            capo: song.capo.map(|capo| capo as u32),
+            note: song.note,
+        };
+        let mut setlist_song = SetlistSong {
+            display_title: song.name.unwrap_or_default(),
+            chords: overrides
+                .chords
+                .clone()
+                .unwrap_or_else(|| song.chords.unwrap_or_default()),
+            chordpro: overrides.chordpro.clone().or(song.chordpro),
+            overrides,
+            deleted: song.deleted_at.is_some(),
+        };
+        setlist_song.transpose(setlist_song.overrides.transpose);
+
+        (song.id, setlist_song)
     })
     .fetch_all(pool)
-    .await
-    {
-        Ok(songs) => songs,
-        Err(_) => Vec::new(),
-    };
+    .await?;
 
     Ok(Setlist {
-        display_title: setlist_display_title,
+        display_title: setlist.display_title,
+        gig: setlist.gig,
         songs,
     })
 }
 
-pub async fn create(pool: &SqlitePool, setlist: NewSetlist) -> Result<i64> {
+pub enum CreateOutcome {
+    Created(i64),
+    SongNotFound(i64),
+}
+
+/// Setlist can be made only of songs from the same library.
+pub async fn create(
+    pool: &SqlitePool,
+    library: &Library,
+    setlist: NewSetlist,
+) -> Result<CreateOutcome> {
+    library.ensure_role(Role::Editor)?;
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
     let mut transaction = pool.begin().await?;
+
+    for song_id in setlist.songs.iter() {
+        if !song_exists(&mut transaction, library, *song_id).await? {
+            return Ok(CreateOutcome::SongNotFound(*song_id));
+        }
+    }
+
     let setlist_id = query!(
         r#"
-                INSERT INTO setlists(display_title)
-                VALUES (?)
+                INSERT INTO setlists(display_title, gig_date, start_time, venue, notes, owner_id, band_id)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
                 "#,
         setlist.display_title,
+        setlist.gig.gig_date,
+        setlist.gig.start_time,
+        setlist.gig.venue,
+        setlist.gig.notes,
+        owner_id,
+        band_id,
     )
     .execute(&mut transaction)
     .await?
     .last_insert_rowid();
 
-    for song_id in setlist.songs {
-        persist_setlist_song_relation(&mut transaction, &setlist_id, &song_id).await?;
+    for (position, song_id) in setlist.songs.iter().enumerate() {
+        persist_setlist_song_relation(&mut transaction, &setlist_id, song_id, position as i64)
+            .await?;
     }
 
     transaction.commit().await?;
 
-    Ok(setlist_id)
+    Ok(CreateOutcome::Created(setlist_id))
+}
+
+/// Returns `false` when there is no setlist to update.
+pub async fn update(
+    pool: &SqlitePool,
+    library: &Library,
+    setlist_id: i64,
+    patch: SetlistPatch,
+) -> Result<bool> {
+    library.ensure_role(Role::Editor)?;
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    let mut transaction = pool.begin().await?;
+
+    let current = match query!(
+        r#"
+SELECT display_title, gig_date, start_time, venue, notes
+FROM setlists
+WHERE id = ?
+AND owner_id IS ?
+AND band_id IS ?
+AND deleted_at IS NULL
+        "#,
+        setlist_id,
+        owner_id,
+        band_id
+    )
+    .fetch_optional(&mut transaction)
+    .await?
+    {
+        Some(current) => current,
+        None => return Ok(false),
+    };
+
+    let display_title = patch.display_title.or(current.display_title);
+    let gig_date = patched(current.gig_date, patch.gig.gig_date);
+    let start_time = patched(current.start_time, patch.gig.start_time);
+    let venue = patched(current.venue, patch.gig.venue);
+    let notes = patched(current.notes, patch.gig.notes);
+
+    query!(
+        r#"
+UPDATE setlists
+SET display_title = ?, gig_date = ?, start_time = ?, venue = ?, notes = ?
+WHERE id = ?
+        "#,
+        display_title,
+        gig_date,
+        start_time,
+        venue,
+        notes,
+        setlist_id
+    )
+    .execute(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(true)
+}
+
+fn patched(current: Option<String>, patch: Option<String>) -> Option<String> {
+    match patch {
+        Some(value) if value.is_empty() => None,
+        Some(value) => Some(value),
+        None => current,
+    }
 }
 
 async fn persist_setlist_song_relation(
     transaction: &mut Transaction<'_, Sqlite>,
     setlist_id: &i64,
     song_id: &i64,
+    position: i64,
 ) -> Result<i64> {
     Ok(query!(
         r#"
-INSERT INTO setlist_to_song_relations(setlist_id, song_id)
-VALUES(?, ?)
+INSERT INTO setlist_to_song_relations(setlist_id, song_id, position)
+VALUES(?, ?, ?)
         "#,
         setlist_id,
         song_id,
+        position,
     )
     .execute(transaction)
     .await?
     .last_insert_rowid())
 }
 
-pub async fn delete(pool: &SqlitePool, setlist_id: i64) -> Result<()> {
+pub enum ReorderOutcome {
+    Reordered,
+    SetlistNotFound,
+    SongsMismatch,
+}
+
+/// Persists new order of songs in setlist. `songs` has to contain exactly
+/// the same song ids as setlist already does, only order may differ.
+pub async fn reorder(
+    pool: &SqlitePool,
+    library: &Library,
+    setlist_id: i64,
+    songs: &[i64],
+) -> Result<ReorderOutcome> {
+    library.ensure_role(Role::Editor)?;
+    let mut transaction = pool.begin().await?;
+
+    if !setlist_exists(&mut transaction, library, setlist_id).await? {
+        return Ok(ReorderOutcome::SetlistNotFound);
+    }
+
+    let mut relations: Vec<(i64, i64)> = query!(
+        r#"
+SELECT id AS "id!", song_id
+FROM setlist_to_song_relations
+WHERE setlist_id = ?
+ORDER BY position, id
+        "#,
+        setlist_id
+    )
+    .map(|relation| (relation.id, relation.song_id.unwrap_or_default()))
+    .fetch_all(&mut transaction)
+    .await?;
+
+    let mut persisted_song_ids: Vec<i64> = relations.iter().map(|(_, song_id)| *song_id).collect();
+    let mut requested_song_ids = songs.to_vec();
+    persisted_song_ids.sort_unstable();
+    requested_song_ids.sort_unstable();
+    if persisted_song_ids != requested_song_ids {
+        return Ok(ReorderOutcome::SongsMismatch);
+    }
+
+    for (position, song_id) in songs.iter().enumerate() {
+        let index = relations
+            .iter()
+            .position(|(_, relation_song_id)| relation_song_id == song_id)
+            .expect("Song ids were compared above");
+        let (relation_id, _) = relations.remove(index);
+        let position = position as i64;
+
+        query!(
+            r#"
+UPDATE setlist_to_song_relations
+SET position = ?
+WHERE id = ?
+            "#,
+            position,
+            relation_id
+        )
+        .execute(&mut transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(ReorderOutcome::Reordered)
+}
+
+pub enum AddSongOutcome {
+    Added,
+    SetlistNotFound,
+    SongNotFound,
+    AlreadyInSetlist,
+}
+
+/// Puts song into setlist at given position, or at its end when position
+/// is not given. Songs placed at or after that position are moved down.
+pub async fn add_song(
+    pool: &SqlitePool,
+    library: &Library,
+    setlist_id: i64,
+    song_id: i64,
+    position: Option<i64>,
+) -> Result<AddSongOutcome> {
+    library.ensure_role(Role::Editor)?;
     let mut transaction = pool.begin().await?;
+
+    if !setlist_exists(&mut transaction, library, setlist_id).await? {
+        return Ok(AddSongOutcome::SetlistNotFound);
+    }
+
+    if !song_exists(&mut transaction, library, song_id).await? {
+        return Ok(AddSongOutcome::SongNotFound);
+    }
+
+    let already_in_setlist = query!(
+        r#"
+SELECT COUNT(*) AS occurrences
+FROM setlist_to_song_relations
+WHERE setlist_id = ?
+AND song_id = ?
+        "#,
+        setlist_id,
+        song_id
+    )
+    .fetch_one(&mut transaction)
+    .await?
+    .occurrences
+        > 0;
+    if already_in_setlist {
+        return Ok(AddSongOutcome::AlreadyInSetlist);
+    }
+
+    let songs_count = query!(
+        r#"
+SELECT COUNT(*) AS songs_count
+FROM setlist_to_song_relations
+WHERE setlist_id = ?
+        "#,
+        setlist_id
+    )
+    .fetch_one(&mut transaction)
+    .await?
+    .songs_count as i64;
+
+    let position = match position {
+        Some(position) => position.clamp(0, songs_count),
+        None => songs_count,
+    };
+
     query!(
         r#"
+UPDATE setlist_to_song_relations
+SET position = position + 1
+WHERE setlist_id = ?
+AND position >= ?
+        "#,
+        setlist_id,
+        position
+    )
+    .execute(&mut transaction)
+    .await?;
+
+    persist_setlist_song_relation(&mut transaction, &setlist_id, &song_id, position).await?;
+
+    transaction.commit().await?;
+
+    Ok(AddSongOutcome::Added)
+}
+
+/// Removes every occurrence of song from setlist, returns number of removed entries.
+pub async fn remove_song(
+    pool: &SqlitePool,
+    library: &Library,
+    setlist_id: i64,
+    song_id: i64,
+) -> Result<u64> {
+    library.ensure_role(Role::Editor)?;
+    let mut transaction = pool.begin().await?;
+
+    if !setlist_exists(&mut transaction, library, setlist_id).await? {
+        return Ok(0);
+    }
+
+    let rows_affected = query!(
+        r#"
 DELETE FROM setlist_to_song_relations
 WHERE setlist_id = ?
+AND song_id = ?
         "#,
-        setlist_id
+        setlist_id,
+        song_id
     )
     .execute(&mut transaction)
+    .await?
+    .rows_affected();
+
+    renumber_songs(&mut transaction, setlist_id).await?;
+
+    transaction.commit().await?;
+
+    Ok(rows_affected)
+}
+
+/// Closes gaps in positions left by removed songs, `add_song` relies on
+/// positions going from 0 to number of songs.
+pub async fn renumber_songs(
+    transaction: &mut Transaction<'_, Sqlite>,
+    setlist_id: i64,
+) -> Result<()> {
+    let relation_ids: Vec<i64> = query!(
+        r#"
+SELECT id AS "id!"
+FROM setlist_to_song_relations
+WHERE setlist_id = ?
+ORDER BY position, id
+        "#,
+        setlist_id
+    )
+    .map(|relation| relation.id)
+    .fetch_all(&mut *transaction)
     .await?;
 
+    for (position, relation_id) in relation_ids.iter().enumerate() {
+        let position = position as i64;
+        query!(
+            r#"
+UPDATE setlist_to_song_relations
+SET position = ?
+WHERE id = ?
+            "#,
+            position,
+            relation_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Changes how song is played in this setlist only, every occurrence of the
+/// song is changed. Returns `false` when song is not in setlist.
+pub async fn update_song_overrides(
+    pool: &SqlitePool,
+    library: &Library,
+    setlist_id: i64,
+    song_id: i64,
+    patch: SetlistSongOverridesPatch,
+) -> Result<bool> {
+    library.ensure_role(Role::Editor)?;
+    if let Some(custom_chordpro) = patch.chordpro.as_deref() {
+        chordpro::parse(custom_chordpro)?;
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    if !setlist_exists(&mut transaction, library, setlist_id).await? {
+        return Ok(false);
+    }
+
+    let current = match query!(
+        r#"
+SELECT custom_chords, custom_chordpro, transpose, capo, note
+FROM setlist_to_song_relations
+WHERE setlist_id = ?
+AND song_id = ?
+ORDER BY position, id
+        "#,
+        setlist_id,
+        song_id
+    )
+    .fetch_optional(&mut transaction)
+    .await?
+    {
+        Some(current) => current,
+        None => return Ok(false),
+    };
+
+    let custom_chords = patched(current.custom_chords, patch.chords);
+    let custom_chordpro = patched(current.custom_chordpro, patch.chordpro);
+    let transpose = patch
+        .transpose
+        .map(|transpose| (transpose % 12) as i64)
+        .unwrap_or(current.transpose);
+    let capo = match patch.capo {
+        Some(0) => None,
+        Some(capo) => Some(capo as i64),
+        None => current.capo,
+    };
+    let note = patched(current.note, patch.note);
+
     query!(
         r#"
-DELETE FROM setlists
+UPDATE setlist_to_song_relations
+SET custom_chords = ?, custom_chordpro = ?, transpose = ?, capo = ?, note = ?
+WHERE setlist_id = ?
+AND song_id = ?
+        "#,
+        custom_chords,
+        custom_chordpro,
+        transpose,
+        capo,
+        note,
+        setlist_id,
+        song_id
+    )
+    .execute(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(true)
+}
+
+async fn setlist_exists(
+    transaction: &mut Transaction<'_, Sqlite>,
+    library: &Library,
+    setlist_id: i64,
+) -> Result<bool> {
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    Ok(query!(
+        r#"
+SELECT id
+FROM setlists
 WHERE id = ?
+AND owner_id IS ?
+AND band_id IS ?
+AND deleted_at IS NULL
         "#,
-        setlist_id
+        setlist_id,
+        owner_id,
+        band_id
+    )
+    .fetch_optional(transaction)
+    .await?
+    .is_some())
+}
+
+async fn song_exists(
+    transaction: &mut Transaction<'_, Sqlite>,
+    library: &Library,
+    song_id: i64,
+) -> Result<bool> {
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    Ok(query!(
+        r#"
+SELECT id
+FROM songs
+WHERE id = ?
+AND owner_id IS ?
+AND band_id IS ?
+AND deleted_at IS NULL
+        "#,
+        song_id,
+        owner_id,
+        band_id
+    )
+    .fetch_optional(transaction)
+    .await?
+    .is_some())
+}
+
+/// Setlist is only flagged as deleted, its songs are kept for history while
+/// its share links stop working. Returns `false` when there is no setlist to delete.
+pub async fn soft_delete(pool: &SqlitePool, library: &Library, setlist_id: i64) -> Result<bool> {
+    library.ensure_role(Role::Editor)?;
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    let timestamp = Utc::now().timestamp();
+    Ok(query!(
+        r#"
+UPDATE setlists SET deleted_at = ?
+WHERE id = ?
+AND owner_id IS ?
+AND band_id IS ?
+AND deleted_at IS NULL
+        "#,
+        timestamp,
+        setlist_id,
+        owner_id,
+        band_id,
+    )
+    .execute(pool)
+    .await?
+    .rows_affected()
+        > 0)
+}
+
+/// Moves setlist out of the list of active ones, archiving it again keeps
+/// the original time. Returns `false` when there is no setlist to archive.
+pub async fn archive(pool: &SqlitePool, library: &Library, setlist_id: i64) -> Result<bool> {
+    library.ensure_role(Role::Editor)?;
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    let timestamp = Utc::now().timestamp();
+    Ok(query!(
+        r#"
+UPDATE setlists SET archived_at = COALESCE(archived_at, ?)
+WHERE id = ?
+AND owner_id IS ?
+AND band_id IS ?
+AND deleted_at IS NULL
+        "#,
+        timestamp,
+        setlist_id,
+        owner_id,
+        band_id,
+    )
+    .execute(pool)
+    .await?
+    .rows_affected()
+        > 0)
+}
+
+/// Returns `false` when there is no setlist to unarchive.
+pub async fn unarchive(pool: &SqlitePool, library: &Library, setlist_id: i64) -> Result<bool> {
+    library.ensure_role(Role::Editor)?;
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    Ok(query!(
+        r#"
+UPDATE setlists SET archived_at = NULL
+WHERE id = ?
+AND owner_id IS ?
+AND band_id IS ?
+AND deleted_at IS NULL
+        "#,
+        setlist_id,
+        owner_id,
+        band_id,
+    )
+    .execute(pool)
+    .await?
+    .rows_affected()
+        > 0)
+}
+
+/// Returns `None` when there is no setlist to share.
+pub async fn create_share(
+    pool: &SqlitePool,
+    library: &Library,
+    setlist_id: i64,
+    token: &str,
+    expires_at: Option<i64>,
+) -> Result<Option<SetlistShare>> {
+    library.ensure_role(Role::Editor)?;
+    let mut transaction = pool.begin().await?;
+
+    if !setlist_exists(&mut transaction, library, setlist_id).await? {
+        return Ok(None);
+    }
+
+    let created_at = Utc::now().timestamp();
+    query!(
+        r#"
+INSERT INTO setlist_shares(token, setlist_id, created_by, created_at, expires_at)
+VALUES (?, ?, ?, ?, ?)
+        "#,
+        token,
+        setlist_id,
+        library.user_id,
+        created_at,
+        expires_at
     )
     .execute(&mut transaction)
     .await?;
 
     transaction.commit().await?;
 
-    Ok(())
+    Ok(Some(SetlistShare {
+        token: token.to_string(),
+        expires_at,
+    }))
+}
+
+/// Links of the setlist which still work, `None` when there is no such setlist.
+pub async fn find_shares(
+    pool: &SqlitePool,
+    library: &Library,
+    setlist_id: i64,
+) -> Result<Option<Vec<SetlistShare>>> {
+    library.ensure_role(Role::Editor)?;
+    let mut transaction = pool.begin().await?;
+
+    if !setlist_exists(&mut transaction, library, setlist_id).await? {
+        return Ok(None);
+    }
+
+    let now = Utc::now().timestamp();
+    Ok(Some(
+        query!(
+            r#"
+SELECT token AS "token!", expires_at
+FROM setlist_shares
+WHERE setlist_id = ?
+AND (expires_at IS NULL OR expires_at > ?)
+ORDER BY created_at
+            "#,
+            setlist_id,
+            now
+        )
+        .map(|share| SetlistShare {
+            token: share.token,
+            expires_at: share.expires_at,
+        })
+        .fetch_all(&mut transaction)
+        .await?,
+    ))
+}
+
+/// Revokes the link, returns `false` when setlist has no such link.
+pub async fn delete_share(
+    pool: &SqlitePool,
+    library: &Library,
+    setlist_id: i64,
+    token: &str,
+) -> Result<bool> {
+    library.ensure_role(Role::Editor)?;
+    let mut transaction = pool.begin().await?;
+
+    if !setlist_exists(&mut transaction, library, setlist_id).await? {
+        return Ok(false);
+    }
+
+    let rows_affected = query!(
+        r#"
+DELETE FROM setlist_shares
+WHERE token = ?
+AND setlist_id = ?
+        "#,
+        token,
+        setlist_id
+    )
+    .execute(&mut transaction)
+    .await?
+    .rows_affected();
+
+    transaction.commit().await?;
+
+    Ok(rows_affected > 0)
+}
+
+/// Setlist behind the link, `None` when link does not exist, was revoked or
+/// has expired. It is read the same way as by a viewer of its library, except
+/// songs in trash are left out.
+pub async fn find_shared(pool: &SqlitePool, token: &str) -> Result<Option<Setlist>> {
+    let now = Utc::now().timestamp();
+    let shared = match query!(
+        r#"
+SELECT s.id AS "id!", s.owner_id, s.band_id, sh.created_by
+FROM setlist_shares sh, setlists s
+WHERE sh.token = ?
+AND (sh.expires_at IS NULL OR sh.expires_at > ?)
+AND s.id = sh.setlist_id
+AND s.deleted_at IS NULL
+        "#,
+        token,
+        now
+    )
+    .fetch_optional(pool)
+    .await?
+    {
+        Some(shared) => shared,
+        None => return Ok(None),
+    };
+
+    let library = Library {
+        user_id: shared.owner_id.unwrap_or(shared.created_by),
+        band_id: shared.band_id,
+        role: Role::Viewer,
+    };
+
+    let mut setlist = find_by_id(pool, &library, shared.id).await?;
+    setlist.songs.retain(|(_, song)| !song.deleted);
+
+    Ok(Some(setlist))
 }
@@ -1,46 +1,425 @@
 use actix_web::{
-    delete, get, post,
-    web::{Data, Json, Path, ServiceConfig},
-    HttpResponse, Responder,
+    delete, get, patch, post, put,
+    web::{Data, Json, Path, Query, ServiceConfig},
+    HttpResponse, Responder, ResponseError,
+};
+use chrono::{Duration, Utc};
+use setlistrs_types::error::{ApiError, FieldError};
+use setlistrs_types::validation::{
+    validate_new_setlist, validate_new_share, validate_setlist_patch,
+};
+use setlistrs_types::{
+    NewSetlist, NewSetlistShare, NewSetlistSong, SetlistExportQuery, SetlistListQuery,
+    SetlistOrder, SetlistPatch, SetlistShareList, SetlistSongOverridesPatch,
 };
-use setlistrs_types::NewSetlist;
 use sqlx::SqlitePool;
 
-use crate::setlist::repository;
+use crate::band::Library;
+use crate::error::error_response;
+use crate::setlist::pdf;
+use crate::setlist::repository::{self, AddSongOutcome, CreateOutcome, ReorderOutcome};
+use crate::song::Transpose;
+use crate::user::new_token;
 
 pub fn init(config: &mut ServiceConfig) {
     config
         .service(find_all)
         .service(find_by_id)
+        .service(export_pdf)
+        .service(export_songbook)
         .service(create)
-        .service(delete);
+        .service(update)
+        .service(reorder)
+        .service(add_song)
+        .service(update_song_overrides)
+        .service(remove_song)
+        .service(share)
+        .service(find_shares)
+        .service(revoke_share)
+        .service(archive)
+        .service(unarchive)
+        .service(delete)
+        .service(find_shared)
+        .service(export_shared_pdf);
 }
 
 #[get("/setlists")]
-async fn find_all(pool: Data<SqlitePool>) -> impl Responder {
-    match repository::find_all(pool.get_ref()).await {
+async fn find_all(
+    pool: Data<SqlitePool>,
+    library: Library,
+    query: Query<SetlistListQuery>,
+) -> impl Responder {
+    match repository::find_all(pool.get_ref(), &library, query.archived).await {
         Ok(setlist_list) => HttpResponse::Ok().json(setlist_list),
-        Err(e) => HttpResponse::InternalServerError().body(format!("{}", e)),
+        Err(e) => error_response(e),
     }
 }
 #[get("/setlists/{setlist_id}")]
-async fn find_by_id(pool: Data<SqlitePool>, setlist_id: Path<i64>) -> impl Responder {
-    match repository::find_by_id(pool.get_ref(), setlist_id.into_inner()).await {
-        Ok(setlist_by_id) => HttpResponse::Ok().json(setlist_by_id),
-        Err(e) => HttpResponse::NotFound().body(format!("{}", e)),
+async fn find_by_id(
+    pool: Data<SqlitePool>,
+    library: Library,
+    setlist_id: Path<i64>,
+    Transpose(semitones): Transpose,
+) -> impl Responder {
+    match repository::find_by_id(pool.get_ref(), &library, setlist_id.into_inner()).await {
+        Ok(mut setlist_by_id) => {
+            for (_, song) in setlist_by_id.songs.iter_mut() {
+                song.transpose(semitones);
+            }
+            HttpResponse::Ok().json(setlist_by_id)
+        }
+        Err(e) => find_error(e),
+    }
+}
+#[get("/setlists/{setlist_id}/export.pdf")]
+async fn export_pdf(
+    pool: Data<SqlitePool>,
+    library: Library,
+    setlist_id: Path<i64>,
+    Transpose(semitones): Transpose,
+    export_query: Query<SetlistExportQuery>,
+) -> impl Responder {
+    let setlist_id = setlist_id.into_inner();
+    match repository::find_by_id(pool.get_ref(), &library, setlist_id).await {
+        Ok(mut setlist) => {
+            // Songs in trash are not played, they are only flagged in the app.
+            setlist.songs.retain(|(_, song)| !song.deleted);
+            for (_, song) in setlist.songs.iter_mut() {
+                song.transpose(semitones);
+            }
+            HttpResponse::Ok()
+                .content_type("application/pdf")
+                .insert_header((
+                    "Content-Disposition",
+                    format!("inline; filename=\"setlist-{}.pdf\"", setlist_id),
+                ))
+                .body(pdf::export(&setlist, export_query.chords))
+        }
+        Err(e) => find_error(e),
+    }
+}
+#[get("/setlists/{setlist_id}/songbook.pdf")]
+async fn export_songbook(
+    pool: Data<SqlitePool>,
+    library: Library,
+    setlist_id: Path<i64>,
+    Transpose(semitones): Transpose,
+) -> impl Responder {
+    let setlist_id = setlist_id.into_inner();
+    match repository::find_by_id(pool.get_ref(), &library, setlist_id).await {
+        Ok(mut setlist) => {
+            // Songs in trash are not played, they are only flagged in the app.
+            setlist.songs.retain(|(_, song)| !song.deleted);
+            for (_, song) in setlist.songs.iter_mut() {
+                song.transpose(semitones);
+            }
+            HttpResponse::Ok()
+                .content_type("application/pdf")
+                .insert_header((
+                    "Content-Disposition",
+                    format!("inline; filename=\"setlist-{}-songbook.pdf\"", setlist_id),
+                ))
+                .body(pdf::songbook(&setlist))
+        }
+        Err(e) => find_error(e),
     }
 }
 #[post("/setlists")]
-async fn create(pool: Data<SqlitePool>, new_setlist: Json<NewSetlist>) -> impl Responder {
-    match repository::create(pool.get_ref(), new_setlist.into_inner()).await {
-        Ok(persisted_setlis) => HttpResponse::Created().json(persisted_setlis),
-        Err(e) => HttpResponse::InternalServerError().body(format!("{}", e)),
+async fn create(
+    pool: Data<SqlitePool>,
+    library: Library,
+    new_setlist: Json<NewSetlist>,
+) -> impl Responder {
+    let field_errors = validate_new_setlist(&new_setlist);
+    if !field_errors.is_empty() {
+        return ApiError::validation(field_errors).error_response();
+    }
+    match repository::create(pool.get_ref(), &library, new_setlist.into_inner()).await {
+        Ok(CreateOutcome::Created(persisted_setlis)) => {
+            HttpResponse::Created().json(persisted_setlis)
+        }
+        Ok(CreateOutcome::SongNotFound(song_id)) => unknown_song("songs", song_id),
+        Err(e) => error_response(e),
     }
 }
-#[delete("/setlists/{setlist_id}")]
-async fn delete(pool: Data<SqlitePool>, setlist_id: Path<i64>) -> impl Responder {
-    match repository::delete(pool.get_ref(), setlist_id.into_inner()).await {
+#[patch("/setlists/{setlist_id}")]
+async fn update(
+    pool: Data<SqlitePool>,
+    library: Library,
+    setlist_id: Path<i64>,
+    setlist_patch: Json<SetlistPatch>,
+) -> impl Responder {
+    let field_errors = validate_setlist_patch(&setlist_patch);
+    if !field_errors.is_empty() {
+        return ApiError::validation(field_errors).error_response();
+    }
+    let setlist_id = setlist_id.into_inner();
+    match repository::update(
+        pool.get_ref(),
+        &library,
+        setlist_id,
+        setlist_patch.into_inner(),
+    )
+    .await
+    {
+        Ok(true) => match repository::find_by_id(pool.get_ref(), &library, setlist_id).await {
+            Ok(setlist) => HttpResponse::Ok().json(setlist),
+            Err(e) => error_response(e),
+        },
+        Ok(false) => setlist_not_found(),
+        Err(e) => error_response(e),
+    }
+}
+#[put("/setlists/{setlist_id}/order")]
+async fn reorder(
+    pool: Data<SqlitePool>,
+    library: Library,
+    setlist_id: Path<i64>,
+    setlist_order: Json<SetlistOrder>,
+) -> impl Responder {
+    let setlist_id = setlist_id.into_inner();
+    match repository::reorder(pool.get_ref(), &library, setlist_id, &setlist_order.songs).await {
+        Ok(ReorderOutcome::Reordered) => {
+            match repository::find_by_id(pool.get_ref(), &library, setlist_id).await {
+                Ok(setlist) => HttpResponse::Ok().json(setlist),
+                Err(e) => error_response(e),
+            }
+        }
+        Ok(ReorderOutcome::SetlistNotFound) => setlist_not_found(),
+        Ok(ReorderOutcome::SongsMismatch) => {
+            ApiError::bad_request("New order has to contain exactly the songs already in setlist")
+                .error_response()
+        }
+        Err(e) => error_response(e),
+    }
+}
+#[post("/setlists/{setlist_id}/songs")]
+async fn add_song(
+    pool: Data<SqlitePool>,
+    library: Library,
+    setlist_id: Path<i64>,
+    new_setlist_song: Json<NewSetlistSong>,
+) -> impl Responder {
+    let setlist_id = setlist_id.into_inner();
+    match repository::add_song(
+        pool.get_ref(),
+        &library,
+        setlist_id,
+        new_setlist_song.song_id,
+        new_setlist_song.position,
+    )
+    .await
+    {
+        Ok(AddSongOutcome::Added) => {
+            match repository::find_by_id(pool.get_ref(), &library, setlist_id).await {
+                Ok(setlist) => HttpResponse::Created().json(setlist),
+                Err(e) => error_response(e),
+            }
+        }
+        Ok(AddSongOutcome::SetlistNotFound) => setlist_not_found(),
+        Ok(AddSongOutcome::SongNotFound) => unknown_song("song_id", new_setlist_song.song_id),
+        Ok(AddSongOutcome::AlreadyInSetlist) => song_already_in_setlist(new_setlist_song.song_id),
+        Err(e) => error_response(e),
+    }
+}
+#[patch("/setlists/{setlist_id}/songs/{song_id}")]
+async fn update_song_overrides(
+    pool: Data<SqlitePool>,
+    library: Library,
+    path: Path<(i64, i64)>,
+    overrides_patch: Json<SetlistSongOverridesPatch>,
+) -> impl Responder {
+    let (setlist_id, song_id) = path.into_inner();
+    match repository::update_song_overrides(
+        pool.get_ref(),
+        &library,
+        setlist_id,
+        song_id,
+        overrides_patch.into_inner(),
+    )
+    .await
+    {
+        Ok(true) => match repository::find_by_id(pool.get_ref(), &library, setlist_id).await {
+            Ok(setlist) => HttpResponse::Ok().json(setlist),
+            Err(e) => error_response(e),
+        },
+        Ok(false) => song_not_in_setlist(),
+        Err(e) => error_response(e),
+    }
+}
+#[delete("/setlists/{setlist_id}/songs/{song_id}")]
+async fn remove_song(
+    pool: Data<SqlitePool>,
+    library: Library,
+    path: Path<(i64, i64)>,
+) -> impl Responder {
+    let (setlist_id, song_id) = path.into_inner();
+    match repository::remove_song(pool.get_ref(), &library, setlist_id, song_id).await {
+        Ok(0) => song_not_in_setlist(),
         Ok(_) => HttpResponse::NoContent().finish(),
-        Err(e) => HttpResponse::InternalServerError().body(format!("{}", e)),
+        Err(e) => error_response(e),
+    }
+}
+#[post("/setlists/{setlist_id}/archive")]
+async fn archive(
+    pool: Data<SqlitePool>,
+    library: Library,
+    setlist_id: Path<i64>,
+) -> impl Responder {
+    match repository::archive(pool.get_ref(), &library, setlist_id.into_inner()).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => setlist_not_found(),
+        Err(e) => error_response(e),
     }
 }
+#[post("/setlists/{setlist_id}/unarchive")]
+async fn unarchive(
+    pool: Data<SqlitePool>,
+    library: Library,
+    setlist_id: Path<i64>,
+) -> impl Responder {
+    match repository::unarchive(pool.get_ref(), &library, setlist_id.into_inner()).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => setlist_not_found(),
+        Err(e) => error_response(e),
+    }
+}
+#[delete("/setlists/{setlist_id}")]
+async fn delete(pool: Data<SqlitePool>, library: Library, setlist_id: Path<i64>) -> impl Responder {
+    match repository::soft_delete(pool.get_ref(), &library, setlist_id.into_inner()).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => setlist_not_found(),
+        Err(e) => error_response(e),
+    }
+}
+
+#[post("/setlists/{setlist_id}/share")]
+async fn share(
+    pool: Data<SqlitePool>,
+    library: Library,
+    setlist_id: Path<i64>,
+    new_share: Option<Json<NewSetlistShare>>,
+) -> impl Responder {
+    let new_share = new_share.map(Json::into_inner).unwrap_or_default();
+    let field_errors = validate_new_share(&new_share);
+    if !field_errors.is_empty() {
+        return ApiError::validation(field_errors).error_response();
+    }
+    let expires_at = new_share
+        .expires_in_days
+        .map(|days| (Utc::now() + Duration::days(days as i64)).timestamp());
+    match repository::create_share(
+        pool.get_ref(),
+        &library,
+        setlist_id.into_inner(),
+        &new_token(),
+        expires_at,
+    )
+    .await
+    {
+        Ok(Some(share)) => HttpResponse::Created().json(share),
+        Ok(None) => setlist_not_found(),
+        Err(e) => error_response(e),
+    }
+}
+#[get("/setlists/{setlist_id}/shares")]
+async fn find_shares(
+    pool: Data<SqlitePool>,
+    library: Library,
+    setlist_id: Path<i64>,
+) -> impl Responder {
+    match repository::find_shares(pool.get_ref(), &library, setlist_id.into_inner()).await {
+        Ok(Some(shares)) => HttpResponse::Ok().json(SetlistShareList { data: shares }),
+        Ok(None) => setlist_not_found(),
+        Err(e) => error_response(e),
+    }
+}
+#[delete("/setlists/{setlist_id}/shares/{token}")]
+async fn revoke_share(
+    pool: Data<SqlitePool>,
+    library: Library,
+    path: Path<(i64, String)>,
+) -> impl Responder {
+    let (setlist_id, token) = path.into_inner();
+    match repository::delete_share(pool.get_ref(), &library, setlist_id, &token).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => ApiError::not_found("Link not found").error_response(),
+        Err(e) => error_response(e),
+    }
+}
+/// Read-only view for people without account, e.g. sound engineer.
+#[get("/shared/setlists/{token}")]
+async fn find_shared(
+    pool: Data<SqlitePool>,
+    token: Path<String>,
+    Transpose(semitones): Transpose,
+) -> impl Responder {
+    match repository::find_shared(pool.get_ref(), &token).await {
+        Ok(Some(mut setlist)) => {
+            for (_, song) in setlist.songs.iter_mut() {
+                song.transpose(semitones);
+            }
+            HttpResponse::Ok().json(setlist)
+        }
+        Ok(None) => shared_link_not_found(),
+        Err(e) => error_response(e),
+    }
+}
+#[get("/shared/setlists/{token}/export.pdf")]
+async fn export_shared_pdf(
+    pool: Data<SqlitePool>,
+    token: Path<String>,
+    Transpose(semitones): Transpose,
+    export_query: Query<SetlistExportQuery>,
+) -> impl Responder {
+    match repository::find_shared(pool.get_ref(), &token).await {
+        Ok(Some(mut setlist)) => {
+            for (_, song) in setlist.songs.iter_mut() {
+                song.transpose(semitones);
+            }
+            HttpResponse::Ok()
+                .content_type("application/pdf")
+                .insert_header(("Content-Disposition", "inline; filename=\"setlist.pdf\""))
+                .body(pdf::export(&setlist, export_query.chords))
+        }
+        Ok(None) => shared_link_not_found(),
+        Err(e) => error_response(e),
+    }
+}
+
+fn setlist_not_found() -> HttpResponse {
+    ApiError::not_found("Setlist not found").error_response()
+}
+
+/// Missing setlist surfaces as `RowNotFound` from `repository::find_by_id`.
+fn find_error(e: anyhow::Error) -> HttpResponse {
+    match e.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::RowNotFound) => setlist_not_found(),
+        _ => error_response(e),
+    }
+}
+
+/// Deleted songs and songs of other libraries are unknown as well.
+fn unknown_song(field: &str, song_id: i64) -> HttpResponse {
+    ApiError::validation(vec![FieldError::new(
+        field,
+        format!("Song {} does not exist", song_id),
+    )])
+    .error_response()
+}
+
+fn song_already_in_setlist(song_id: i64) -> HttpResponse {
+    ApiError::validation(vec![FieldError::new(
+        "song_id",
+        format!("Song {} is already in setlist", song_id),
+    )])
+    .error_response()
+}
+
+fn song_not_in_setlist() -> HttpResponse {
+    ApiError::not_found("Song is not in setlist").error_response()
+}
+
+fn shared_link_not_found() -> HttpResponse {
+    ApiError::not_found("Link was revoked or has expired").error_response()
+}
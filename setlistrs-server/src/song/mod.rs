@@ -1,5 +1,7 @@
 mod repository;
 mod routes;
+mod transpose;
 
 // pub use repository::*;
 pub use routes::init;
+pub use transpose::Transpose;
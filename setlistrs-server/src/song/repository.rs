@@ -1,140 +1,219 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
 use chrono::Utc;
-use setlistrs_types::{Song, YTLink};
+use setlistrs_types::{
+    chordpro, DeletedSong, Role, Song, SongDetails, SongPatch, YTLink, YTLinkDetails,
+};
 use sqlx::{query, Sqlite, SqlitePool, Transaction};
 
-pub async fn find_all(pool: &SqlitePool) -> Result<Vec<(i64, Song)>> {
-    let songs: Vec<(i64, Song)> = query!(
+use crate::band::Library;
+use crate::setlist::renumber_songs;
+
+pub async fn find_all(pool: &SqlitePool, library: &Library) -> Result<Vec<(i64, Song)>> {
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    let songs = query!(
         r#"
-SELECT id, name, chords 
+SELECT id, name, chords, chordpro
 FROM songs
 WHERE deleted_at IS NULL
+AND owner_id IS ?
+AND band_id IS ?
 ORDER BY id
-        "#
+        "#,
+        owner_id,
+        band_id
     )
     .fetch_all(pool)
-    .await?
-    .into_iter()
-    .map(|song| {
-        (
-            song.id,
-            Song {
-                name: match song.name {
-                    Some(name) => name.into(),
-                    None => "".into(),
-                },
-                source: Vec::new(),
-                chords: match song.chords {
-                    Some(chords) => chords.into(),
-                    None => "".into(),
-                },
-                cover: None,
-            },
-        )
-    })
-    .collect();
-
-    let song_ids = songs.iter().map(|(song_id, _song)| song_id);
-    let mut covers: Vec<(i64, Vec<YTLink>)> = Vec::new();
-    for song_id in song_ids.clone() {
-        covers.push((song_id.clone(), obtain_covers(pool, song_id).await?));
-    }
+    .await?;
 
-    let mut sources: Vec<(i64, Vec<YTLink>)> = Vec::new();
-    for song_id in song_ids {
-        sources.push((song_id.clone(), obtain_sources(pool, song_id).await?));
-    }
+    let mut sources = obtain_all_sources(pool, library).await?;
+    let mut covers = obtain_all_covers(pool, library).await?;
 
-    let songs_with_relations: Vec<(i64, Song)> = songs
+    Ok(songs
         .into_iter()
-        .map(|(song_id, song)| {
-            let song_sources: Vec<YTLink> = match sources
-                .iter()
-                .find(|(source_song_id, _yt_link)| *source_song_id == song_id)
-                .map(|(_source_song_id, yt_link)| yt_link)
-                .cloned()
-            {
-                Some(links) => links,
-                None => panic!(),
-            };
-
-            let song_covers: Vec<YTLink> = match covers
-                .iter()
-                .find(|(cover_song_id, _yt_link)| *cover_song_id == song_id)
-                .map(|(_cover_song_id, yt_link)| yt_link)
-                .cloned()
-            {
-                Some(links) => links,
-                None => panic!(),
-            };
-
+        .map(|song| {
             (
-                song_id,
+                song.id,
                 Song {
-                    name: song.name,
-                    chords: song.chords,
-                    source: song_sources,
-                    cover: Some(song_covers),
+                    name: song.name.unwrap_or_default(),
+                    source: sources.remove(&song.id).unwrap_or_default(),
+                    cover: Some(covers.remove(&song.id).unwrap_or_default()),
+                    chords: song.chords.unwrap_or_default(),
+                    chordpro: song.chordpro,
                 },
             )
         })
-        .collect();
+        .collect())
+}
 
-    Ok(songs_with_relations)
+/// Covers of all not deleted songs in the library, grouped by song id.
+async fn obtain_all_covers(
+    pool: &SqlitePool,
+    library: &Library,
+) -> Result<HashMap<i64, Vec<YTLink>>> {
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    let mut covers: HashMap<i64, Vec<YTLink>> = HashMap::new();
+    for link in query!(
+        r#"
+SELECT c.song_id AS "song_id!", l.url, l.display_title
+FROM covers c, links l, songs s
+WHERE l.id = c.link_id
+AND s.id = c.song_id
+AND s.deleted_at IS NULL
+AND s.owner_id IS ?
+AND s.band_id IS ?
+ORDER BY c.id
+            "#,
+        owner_id,
+        band_id
+    )
+    .fetch_all(pool)
+    .await?
+    {
+        covers.entry(link.song_id).or_default().push(YTLink {
+            url: link.url.unwrap_or_default(),
+            display_title: link.display_title,
+        });
+    }
+
+    Ok(covers)
 }
 
-async fn obtain_covers(pool: &SqlitePool, song_id: &i64) -> Result<Vec<YTLink>> {
+/// Sources of all not deleted songs in the library, grouped by song id.
+async fn obtain_all_sources(
+    pool: &SqlitePool,
+    library: &Library,
+) -> Result<HashMap<i64, Vec<YTLink>>> {
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    let mut sources: HashMap<i64, Vec<YTLink>> = HashMap::new();
+    for link in query!(
+        r#"
+SELECT so.song_id AS "song_id!", l.url, l.display_title
+FROM sources so, links l, songs s
+WHERE l.id = so.link_id
+AND s.id = so.song_id
+AND s.deleted_at IS NULL
+AND s.owner_id IS ?
+AND s.band_id IS ?
+ORDER BY so.id
+            "#,
+        owner_id,
+        band_id
+    )
+    .fetch_all(pool)
+    .await?
+    {
+        sources.entry(link.song_id).or_default().push(YTLink {
+            url: link.url.unwrap_or_default(),
+            display_title: link.display_title,
+        });
+    }
+
+    Ok(sources)
+}
+
+pub async fn find_by_id(
+    pool: &SqlitePool,
+    library: &Library,
+    song_id: i64,
+) -> Result<Option<SongDetails>> {
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    let song = match query!(
+        r#"
+SELECT id, name, chords, chordpro
+FROM songs
+WHERE id = ?
+AND owner_id IS ?
+AND band_id IS ?
+AND deleted_at IS NULL
+        "#,
+        song_id,
+        owner_id,
+        band_id
+    )
+    .fetch_optional(pool)
+    .await?
+    {
+        Some(song) => song,
+        None => return Ok(None),
+    };
+
+    let sources = obtain_source_details(pool, &song.id).await?;
+    let covers = obtain_cover_details(pool, &song.id).await?;
+
+    Ok(Some(SongDetails {
+        id: song.id,
+        name: song.name.unwrap_or_default(),
+        source: sources,
+        cover: Some(covers),
+        chords: song.chords.unwrap_or_default(),
+        chordpro: song.chordpro,
+    }))
+}
+
+async fn obtain_cover_details(pool: &SqlitePool, song_id: &i64) -> Result<Vec<YTLinkDetails>> {
     Ok(query!(
         r#"
-SELECT l.url, l.display_title FROM covers c, links l 
+SELECT l.id, l.url, l.display_title FROM covers c, links l 
 WHERE c.song_id = ? 
 AND l.id = c.link_id
 ORDER BY c.id
             "#,
         song_id
     )
-    .map(|link| YTLink {
-        url: match link.url {
-            Some(url) => url.into(),
-            None => "".into(),
-        },
+    .map(|link| YTLinkDetails {
+        id: link.id,
+        url: link.url.unwrap_or_default(),
         display_title: link.display_title,
     })
     .fetch_all(pool)
     .await?)
 }
 
-async fn obtain_sources(pool: &SqlitePool, song_id: &i64) -> Result<Vec<YTLink>> {
+async fn obtain_source_details(pool: &SqlitePool, song_id: &i64) -> Result<Vec<YTLinkDetails>> {
     Ok(query!(
         r#"
-SELECT l.url, l.display_title FROM sources s, links l 
+SELECT l.id, l.url, l.display_title FROM sources s, links l 
 WHERE s.song_id = ? 
 AND l.id = s.link_id
 ORDER BY s.id
             "#,
         song_id
     )
-    .map(|link| YTLink {
-        url: match link.url {
-            Some(url) => url.into(),
-            None => "".into(),
-        },
+    .map(|link| YTLinkDetails {
+        id: link.id,
+        url: link.url.unwrap_or_default(),
         display_title: link.display_title,
     })
     .fetch_all(pool)
     .await?)
 }
 
-pub async fn create(pool: &SqlitePool, song: Song) -> Result<Song> {
+pub async fn create(pool: &SqlitePool, library: &Library, song: Song) -> Result<Song> {
+    library.ensure_role(Role::Editor)?;
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    if let Some(body) = &song.chordpro {
+        chordpro::parse(body)?;
+    }
+
     let mut transaction = pool.begin().await?;
 
     let song_id = query!(
         r#"
-        INSERT INTO songs(name, chords)
-        VALUES(?, ?)
+        INSERT INTO songs(name, chords, chordpro, owner_id, band_id)
+        VALUES(?, ?, ?, ?, ?)
         "#,
         song.name,
-        song.chords
+        song.chords,
+        song.chordpro,
+        owner_id,
+        band_id
     )
     .execute(&mut transaction)
     .await?
@@ -142,24 +221,17 @@ pub async fn create(pool: &SqlitePool, song: Song) -> Result<Song> {
 
     let mut source_link_ids = Vec::with_capacity(song.source.len());
     for yt_link in &song.source {
-        source_link_ids.push(persist_link(&mut transaction, &yt_link).await?);
+        source_link_ids.push(persist_link(&mut transaction, yt_link).await?);
     }
 
-    let cover_link_ids = match &song.cover {
-        Some(yt_links) => {
-            let mut cover_link_ids = Vec::new();
-            for yt_link in yt_links {
-                cover_link_ids.push(persist_link(&mut transaction, &yt_link).await?);
-            }
-
-            cover_link_ids
-        }
-        None => todo!(),
-    };
+    let mut cover_link_ids = Vec::new();
+    for yt_link in song.cover.iter().flatten() {
+        cover_link_ids.push(persist_link(&mut transaction, yt_link).await?);
+    }
     for source_link_id in source_link_ids {
         persist_song_link_relation(
             &mut transaction,
-            LinkRelationType::Source,
+            &LinkRelationType::Source,
             song_id,
             source_link_id,
         )
@@ -168,7 +240,7 @@ pub async fn create(pool: &SqlitePool, song: Song) -> Result<Song> {
     for cover_link_id in cover_link_ids {
         persist_song_link_relation(
             &mut transaction,
-            LinkRelationType::Cover,
+            &LinkRelationType::Cover,
             song_id,
             cover_link_id,
         )
@@ -180,6 +252,212 @@ pub async fn create(pool: &SqlitePool, song: Song) -> Result<Song> {
     Ok(song)
 }
 
+pub async fn update(
+    pool: &SqlitePool,
+    library: &Library,
+    song_id: i64,
+    song: SongPatch,
+) -> Result<Option<SongDetails>> {
+    library.ensure_role(Role::Editor)?;
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    let chordpro = match song.chordpro.as_deref() {
+        Some("") => Some(None),
+        Some(body) => {
+            chordpro::parse(body)?;
+            Some(Some(body))
+        }
+        None => None,
+    };
+
+    let mut transaction = pool.begin().await?;
+
+    let rows_affected = query!(
+        r#"
+UPDATE songs
+SET name = COALESCE(?, name), chords = COALESCE(?, chords)
+WHERE id = ?
+AND owner_id IS ?
+AND band_id IS ?
+AND deleted_at IS NULL
+        "#,
+        song.name,
+        song.chords,
+        song_id,
+        owner_id,
+        band_id,
+    )
+    .execute(&mut transaction)
+    .await?
+    .rows_affected();
+
+    if rows_affected == 0 {
+        return Ok(None);
+    }
+
+    if let Some(chordpro) = chordpro {
+        query!(
+            r#"
+UPDATE songs
+SET chordpro = ?
+WHERE id = ?
+            "#,
+            chordpro,
+            song_id,
+        )
+        .execute(&mut transaction)
+        .await?;
+    }
+
+    if let Some(yt_links) = &song.source {
+        sync_song_links(
+            &mut transaction,
+            LinkRelationType::Source,
+            song_id,
+            yt_links,
+        )
+        .await?;
+    }
+    if let Some(yt_links) = &song.cover {
+        sync_song_links(&mut transaction, LinkRelationType::Cover, song_id, yt_links).await?;
+    }
+
+    transaction.commit().await?;
+
+    find_by_id(pool, library, song_id).await
+}
+
+struct PersistedSongLink {
+    relation_id: i64,
+    link_id: i64,
+    yt_link: YTLink,
+}
+
+/// Brings link rows of given relation type in line with `yt_links`.
+/// Links that did not change are kept untouched, missing ones are removed
+/// and new ones are appended.
+async fn sync_song_links(
+    transaction: &mut Transaction<'_, Sqlite>,
+    link_type: LinkRelationType,
+    song_id: i64,
+    yt_links: &[YTLink],
+) -> Result<()> {
+    let mut persisted: Vec<PersistedSongLink> = match link_type {
+        LinkRelationType::Cover => {
+            query!(
+                r#"
+SELECT c.id AS relation_id, l.id AS link_id, l.url, l.display_title
+FROM covers c, links l
+WHERE c.song_id = ?
+AND l.id = c.link_id
+ORDER BY c.id
+            "#,
+                song_id
+            )
+            .map(|row| PersistedSongLink {
+                relation_id: row.relation_id,
+                link_id: row.link_id,
+                yt_link: YTLink {
+                    url: row.url.unwrap_or_default(),
+                    display_title: row.display_title,
+                },
+            })
+            .fetch_all(&mut *transaction)
+            .await?
+        }
+        LinkRelationType::Source => {
+            query!(
+                r#"
+SELECT s.id AS relation_id, l.id AS link_id, l.url, l.display_title
+FROM sources s, links l
+WHERE s.song_id = ?
+AND l.id = s.link_id
+ORDER BY s.id
+            "#,
+                song_id
+            )
+            .map(|row| PersistedSongLink {
+                relation_id: row.relation_id,
+                link_id: row.link_id,
+                yt_link: YTLink {
+                    url: row.url.unwrap_or_default(),
+                    display_title: row.display_title,
+                },
+            })
+            .fetch_all(&mut *transaction)
+            .await?
+        }
+    };
+
+    let mut new_links = Vec::new();
+    for yt_link in yt_links {
+        match persisted.iter().position(|persisted_link| {
+            persisted_link.yt_link.url == yt_link.url
+                && persisted_link.yt_link.display_title == yt_link.display_title
+        }) {
+            Some(position) => {
+                persisted.remove(position);
+            }
+            None => new_links.push(yt_link),
+        }
+    }
+
+    for stale_link in persisted {
+        remove_song_link_relation(
+            &mut *transaction,
+            &link_type,
+            stale_link.relation_id,
+            stale_link.link_id,
+        )
+        .await?;
+    }
+
+    for yt_link in new_links {
+        let link_id = persist_link(&mut *transaction, yt_link).await?;
+        persist_song_link_relation(&mut *transaction, &link_type, song_id, link_id).await?;
+    }
+
+    Ok(())
+}
+
+async fn remove_song_link_relation(
+    transaction: &mut Transaction<'_, Sqlite>,
+    link_type: &LinkRelationType,
+    relation_id: i64,
+    link_id: i64,
+) -> Result<()> {
+    match link_type {
+        LinkRelationType::Cover => query!(
+            r#"
+            DELETE FROM covers
+            WHERE id = ?
+               "#,
+            relation_id
+        ),
+        LinkRelationType::Source => query!(
+            r#"
+            DELETE FROM sources
+            WHERE id = ?
+               "#,
+            relation_id
+        ),
+    }
+    .execute(&mut *transaction)
+    .await?;
+
+    query!(
+        r#"
+DELETE FROM links
+WHERE id = ?
+        "#,
+        link_id
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    Ok(())
+}
+
 async fn persist_link(transaction: &mut Transaction<'_, Sqlite>, yt_link: &YTLink) -> Result<i64> {
     Ok(query!(
         r#"
@@ -201,7 +479,7 @@ enum LinkRelationType {
 
 async fn persist_song_link_relation(
     transaction: &mut Transaction<'_, Sqlite>,
-    link_type: LinkRelationType,
+    link_type: &LinkRelationType,
     song_id: i64,
     link_id: i64,
 ) -> Result<i64> {
@@ -228,16 +506,272 @@ async fn persist_song_link_relation(
     .last_insert_rowid())
 }
 
-pub async fn soft_delete(pool: &SqlitePool, song_id: i64) -> Result<i64> {
+pub async fn soft_delete(pool: &SqlitePool, library: &Library, song_id: i64) -> Result<i64> {
+    library.ensure_role(Role::Editor)?;
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
     let timestamp = Utc::now().timestamp();
     Ok(query!(
         r#"
-UPDATE songs SET deleted_at = ? WHERE id = ?
+UPDATE songs SET deleted_at = ?
+WHERE id = ?
+AND owner_id IS ?
+AND band_id IS ?
+AND deleted_at IS NULL
         "#,
         timestamp,
         song_id,
+        owner_id,
+        band_id,
     )
     .execute(pool)
     .await?
     .rows_affected() as i64)
 }
+
+/// Songs in trash of the library, most recently deleted first.
+pub async fn find_trash(pool: &SqlitePool, library: &Library) -> Result<Vec<DeletedSong>> {
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    Ok(query!(
+        r#"
+SELECT id AS "id!", name, deleted_at AS "deleted_at!"
+FROM songs
+WHERE deleted_at IS NOT NULL
+AND owner_id IS ?
+AND band_id IS ?
+ORDER BY deleted_at DESC, id
+        "#,
+        owner_id,
+        band_id
+    )
+    .map(|song| DeletedSong {
+        id: song.id,
+        name: song.name.unwrap_or_default(),
+        deleted_at: song.deleted_at,
+    })
+    .fetch_all(pool)
+    .await?)
+}
+
+/// Takes song out of trash, `None` when there is no such song in trash.
+pub async fn restore(
+    pool: &SqlitePool,
+    library: &Library,
+    song_id: i64,
+) -> Result<Option<SongDetails>> {
+    library.ensure_role(Role::Editor)?;
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    let rows_affected = query!(
+        r#"
+UPDATE songs SET deleted_at = NULL
+WHERE id = ?
+AND owner_id IS ?
+AND band_id IS ?
+AND deleted_at IS NOT NULL
+        "#,
+        song_id,
+        owner_id,
+        band_id,
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    if rows_affected == 0 {
+        return Ok(None);
+    }
+
+    find_by_id(pool, library, song_id).await
+}
+
+/// Removes song for good, whether it is in trash or not, together with its
+/// links and its entries in setlists. Returns `false` when there is no such song.
+pub async fn purge(pool: &SqlitePool, library: &Library, song_id: i64) -> Result<bool> {
+    library.ensure_role(Role::Editor)?;
+    let owner_id = library.owner_id();
+    let band_id = library.band_id;
+    let mut transaction = pool.begin().await?;
+
+    let song = query!(
+        r#"
+SELECT id
+FROM songs
+WHERE id = ?
+AND owner_id IS ?
+AND band_id IS ?
+        "#,
+        song_id,
+        owner_id,
+        band_id
+    )
+    .fetch_optional(&mut transaction)
+    .await?;
+    if song.is_none() {
+        return Ok(false);
+    }
+
+    sync_song_links(&mut transaction, LinkRelationType::Source, song_id, &[]).await?;
+    sync_song_links(&mut transaction, LinkRelationType::Cover, song_id, &[]).await?;
+
+    let setlist_ids: Vec<i64> = query!(
+        r#"
+SELECT DISTINCT setlist_id AS "setlist_id!"
+FROM setlist_to_song_relations
+WHERE song_id = ?
+        "#,
+        song_id
+    )
+    .map(|relation| relation.setlist_id)
+    .fetch_all(&mut transaction)
+    .await?;
+
+    query!(
+        r#"
+DELETE FROM setlist_to_song_relations
+WHERE song_id = ?
+        "#,
+        song_id
+    )
+    .execute(&mut transaction)
+    .await?;
+
+    for setlist_id in setlist_ids {
+        renumber_songs(&mut transaction, setlist_id).await?;
+    }
+
+    query!(
+        r#"
+DELETE FROM songs
+WHERE id = ?
+        "#,
+        song_id
+    )
+    .execute(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::raw::{c_int, c_uint, c_void};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+    use crate::test_util;
+
+    const SONG_COUNT: i64 = 3000;
+
+    /// `sqlite3_trace_v2` callback, counts statements the connection starts
+    /// running into the `AtomicUsize` given as context.
+    unsafe extern "C" fn count_statement(
+        _event: c_uint,
+        queries: *mut c_void,
+        _statement: *mut c_void,
+        _sql: *mut c_void,
+    ) -> c_int {
+        (*(queries as *const AtomicUsize)).fetch_add(1, Ordering::SeqCst);
+        0
+    }
+
+    /// In-memory database with `SONG_COUNT` songs of user 1, each with two
+    /// sources and one cover, and a song of user 2 which should never show up.
+    /// Every statement run on it is counted in `queries`.
+    async fn seeded_pool(queries: &'static AtomicUsize) -> SqlitePool {
+        let options = SqlitePoolOptions::new().after_connect(move |connection, _| {
+            Box::pin(async move {
+                let mut handle = connection.lock_handle().await?;
+                // Safety: `queries` outlives the connection, the handle is
+                // locked out from the worker thread while tracing is set up.
+                unsafe {
+                    libsqlite3_sys::sqlite3_trace_v2(
+                        handle.as_raw_handle().as_ptr(),
+                        libsqlite3_sys::SQLITE_TRACE_STMT as c_uint,
+                        Some(count_statement),
+                        queries as *const AtomicUsize as *mut c_void,
+                    );
+                }
+                Ok(())
+            })
+        });
+        let pool = test_util::pool_with(options).await;
+
+        let mut transaction = pool.begin().await.unwrap();
+        for user in ["alice", "bob"] {
+            sqlx::query("INSERT INTO users(username, password_hash, created_at) VALUES(?, '', 0)")
+                .bind(user)
+                .execute(&mut transaction)
+                .await
+                .unwrap();
+        }
+        for song_id in 1..=SONG_COUNT + 1 {
+            let owner_id = if song_id > SONG_COUNT { 2 } else { 1 };
+            sqlx::query("INSERT INTO songs(id, name, chords, owner_id) VALUES(?, ?, 'G D', ?)")
+                .bind(song_id)
+                .bind(format!("Song {}", song_id))
+                .bind(owner_id)
+                .execute(&mut transaction)
+                .await
+                .unwrap();
+            for (table, link) in [("sources", "a"), ("sources", "b"), ("covers", "c")] {
+                let link_id = sqlx::query("INSERT INTO links(display_title, url) VALUES(?, ?)")
+                    .bind(format!("{} {}", link, song_id))
+                    .bind(format!("https://youtu.be/{}{}", link, song_id))
+                    .execute(&mut transaction)
+                    .await
+                    .unwrap()
+                    .last_insert_rowid();
+                sqlx::query(&format!(
+                    "INSERT INTO {}(song_id, link_id) VALUES(?, ?)",
+                    table
+                ))
+                .bind(song_id)
+                .bind(link_id)
+                .execute(&mut transaction)
+                .await
+                .unwrap();
+            }
+        }
+        transaction.commit().await.unwrap();
+
+        pool
+    }
+
+    #[actix_web::test]
+    async fn find_all_groups_links_without_query_per_song() {
+        let queries = Box::leak(Box::new(AtomicUsize::new(0)));
+        let pool = seeded_pool(queries).await;
+
+        let queries_before = queries.load(Ordering::SeqCst);
+        let songs = find_all(&pool, &Library::personal(1)).await.unwrap();
+        let queries = queries.load(Ordering::SeqCst) - queries_before;
+
+        assert_eq!(songs.len(), SONG_COUNT as usize);
+        for (song_id, song) in &songs {
+            assert_eq!(song.name, format!("Song {}", song_id));
+            let sources: Vec<_> = song.source.iter().map(|link| link.url.as_str()).collect();
+            assert_eq!(
+                sources,
+                [
+                    format!("https://youtu.be/a{}", song_id),
+                    format!("https://youtu.be/b{}", song_id)
+                ]
+            );
+            let covers = song.cover.as_ref().unwrap();
+            assert_eq!(covers.len(), 1);
+            assert_eq!(
+                covers[0].display_title.as_deref(),
+                Some(format!("c {}", song_id).as_str())
+            );
+        }
+
+        // Songs, their sources and their covers, regardless of song count.
+        assert_eq!(queries, 3);
+    }
+}
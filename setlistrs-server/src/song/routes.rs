@@ -1,39 +1,183 @@
 use actix_web::{
-    delete, get, post,
-    web::{Data, Json, Path, ServiceConfig},
-    HttpResponse, Responder,
+    delete, get, patch, post, put,
+    web::{Data, Json, Path, Query, ServiceConfig},
+    HttpResponse, Responder, ResponseError,
 };
-use setlistrs_types::{Song, SongList};
+use setlistrs_types::error::ApiError;
+use setlistrs_types::validation::{validate_song, validate_song_patch};
+use setlistrs_types::{DeletedSongList, Song, SongDeleteQuery, SongList, SongPatch};
 use sqlx::SqlitePool;
 
-use crate::song::repository;
+use crate::band::Library;
+use crate::error::error_response;
+use crate::song::{repository, Transpose};
+use crate::songbook::{self, SongbookSong};
 
 pub fn init(config: &mut ServiceConfig) {
-    config.service(find_all).service(create).service(delete);
+    config
+        .service(find_all)
+        // Has to be registered before `/songs/{song_id}`, which would match it too.
+        .service(find_trash)
+        .service(find_by_id)
+        .service(export_songbook)
+        .service(create)
+        .service(replace)
+        .service(update)
+        .service(restore)
+        .service(delete);
 }
 
 #[get("/songs")]
-async fn find_all(pool: Data<SqlitePool>) -> impl Responder {
-    match repository::find_all(pool.get_ref()).await {
+async fn find_all(pool: Data<SqlitePool>, library: Library) -> impl Responder {
+    match repository::find_all(pool.get_ref(), &library).await {
         Ok(songs) => HttpResponse::Ok().json(SongList { data: songs }),
-        Err(e) => HttpResponse::InternalServerError().body(format!("{}", e)),
+        Err(e) => error_response(e),
+    }
+}
+#[get("/songs/trash")]
+async fn find_trash(pool: Data<SqlitePool>, library: Library) -> impl Responder {
+    match repository::find_trash(pool.get_ref(), &library).await {
+        Ok(songs) => HttpResponse::Ok().json(DeletedSongList { data: songs }),
+        Err(e) => error_response(e),
+    }
+}
+#[get("/songs/{song_id}")]
+async fn find_by_id(
+    pool: Data<SqlitePool>,
+    library: Library,
+    song_id: Path<i64>,
+    Transpose(semitones): Transpose,
+) -> impl Responder {
+    match repository::find_by_id(pool.get_ref(), &library, song_id.into_inner()).await {
+        Ok(Some(mut song)) => {
+            song.transpose(semitones);
+            HttpResponse::Ok().json(song)
+        }
+        Ok(None) => song_not_found(),
+        Err(e) => error_response(e),
+    }
+}
+#[get("/songs/{song_id}/songbook.pdf")]
+async fn export_songbook(
+    pool: Data<SqlitePool>,
+    library: Library,
+    song_id: Path<i64>,
+    Transpose(semitones): Transpose,
+) -> impl Responder {
+    let song_id = song_id.into_inner();
+    match repository::find_by_id(pool.get_ref(), &library, song_id).await {
+        Ok(Some(mut song)) => {
+            song.transpose(semitones);
+            let pdf = songbook::render(
+                &song.name,
+                &[SongbookSong {
+                    title: &song.name,
+                    details: None,
+                    chords: &song.chords,
+                    chordpro: song.chordpro.as_deref(),
+                }],
+            );
+            HttpResponse::Ok()
+                .content_type("application/pdf")
+                .insert_header((
+                    "Content-Disposition",
+                    format!("inline; filename=\"song-{}.pdf\"", song_id),
+                ))
+                .body(pdf)
+        }
+        Ok(None) => song_not_found(),
+        Err(e) => error_response(e),
     }
 }
 #[post("/songs")]
-async fn create(song: Json<Song>, pool: Data<SqlitePool>) -> impl Responder {
-    match repository::create(pool.get_ref(), song.into_inner()).await {
+async fn create(song: Json<Song>, pool: Data<SqlitePool>, library: Library) -> impl Responder {
+    let field_errors = validate_song(&song);
+    if !field_errors.is_empty() {
+        return ApiError::validation(field_errors).error_response();
+    }
+    match repository::create(pool.get_ref(), &library, song.into_inner()).await {
         Ok(song) => HttpResponse::Created().json(song),
-        Err(e) => HttpResponse::InternalServerError().body(format!("{}", e)),
+        Err(e) => error_response(e),
+    }
+}
+#[put("/songs/{song_id}")]
+async fn replace(
+    pool: Data<SqlitePool>,
+    library: Library,
+    song_id: Path<i64>,
+    song: Json<Song>,
+) -> impl Responder {
+    let field_errors = validate_song(&song);
+    if !field_errors.is_empty() {
+        return ApiError::validation(field_errors).error_response();
+    }
+    match repository::update(
+        pool.get_ref(),
+        &library,
+        song_id.into_inner(),
+        song.into_inner().into(),
+    )
+    .await
+    {
+        Ok(Some(song)) => HttpResponse::Ok().json(song),
+        Ok(None) => song_not_found(),
+        Err(e) => error_response(e),
+    }
+}
+#[patch("/songs/{song_id}")]
+async fn update(
+    pool: Data<SqlitePool>,
+    library: Library,
+    song_id: Path<i64>,
+    song_patch: Json<SongPatch>,
+) -> impl Responder {
+    let field_errors = validate_song_patch(&song_patch);
+    if !field_errors.is_empty() {
+        return ApiError::validation(field_errors).error_response();
+    }
+    match repository::update(
+        pool.get_ref(),
+        &library,
+        song_id.into_inner(),
+        song_patch.into_inner(),
+    )
+    .await
+    {
+        Ok(Some(song)) => HttpResponse::Ok().json(song),
+        Ok(None) => song_not_found(),
+        Err(e) => error_response(e),
+    }
+}
+#[post("/songs/{song_id}/restore")]
+async fn restore(pool: Data<SqlitePool>, library: Library, song_id: Path<i64>) -> impl Responder {
+    match repository::restore(pool.get_ref(), &library, song_id.into_inner()).await {
+        Ok(Some(song)) => HttpResponse::Ok().json(song),
+        Ok(None) => ApiError::not_found("Song not found in trash").error_response(),
+        Err(e) => error_response(e),
     }
 }
 #[delete("/songs/{song_id}")]
-async fn delete(pool: Data<SqlitePool>, song_id: Path<i64>) -> impl Responder {
-    match repository::soft_delete(pool.get_ref(), song_id.into_inner()).await {
-        Ok(rows_affected) => match rows_affected {
-            1 => HttpResponse::NoContent(),
-            _ => HttpResponse::BadRequest(),
-        }
-        .finish(),
-        Err(e) => HttpResponse::InternalServerError().body(format!("{}", e)),
+async fn delete(
+    pool: Data<SqlitePool>,
+    library: Library,
+    song_id: Path<i64>,
+    query: Query<SongDeleteQuery>,
+) -> impl Responder {
+    let song_id = song_id.into_inner();
+    if query.purge {
+        return match repository::purge(pool.get_ref(), &library, song_id).await {
+            Ok(true) => HttpResponse::NoContent().finish(),
+            Ok(false) => song_not_found(),
+            Err(e) => error_response(e),
+        };
+    }
+    match repository::soft_delete(pool.get_ref(), &library, song_id).await {
+        Ok(1) => HttpResponse::NoContent().finish(),
+        Ok(_) => song_not_found(),
+        Err(e) => error_response(e),
     }
 }
+
+fn song_not_found() -> HttpResponse {
+    ApiError::not_found("Song not found").error_response()
+}
@@ -0,0 +1,70 @@
+use std::future::{ready, Ready};
+
+use actix_web::{web::Query, FromRequest, HttpRequest};
+use setlistrs_types::error::ApiError;
+use setlistrs_types::TransposeQuery;
+
+/// Semitones songs of a request are transposed by, read from `?transpose=`.
+/// Missing query means no transposition, invalid one is answered with 400.
+pub struct Transpose(pub i32);
+
+impl FromRequest for Transpose {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
+        let semitones = Query::<TransposeQuery>::from_query(req.query_string())
+            .map_err(|e| e.to_string())
+            .and_then(|query| query.semitones().map_err(|e| e.to_string()));
+
+        ready(match semitones {
+            Ok(semitones) => Ok(Transpose(semitones)),
+            Err(e) => Err(ApiError::bad_request(format!("Invalid transpose, {}", e)).into()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use setlistrs_types::error::ErrorCode;
+
+    use super::*;
+    use crate::test_util::{self, bearer};
+
+    #[actix_web::test]
+    async fn rejects_invalid_transpose() {
+        let pool = test_util::pool().await;
+        let (_, token) = test_util::user(&pool, "ann").await;
+        let app = test::init_service(test_util::app(&pool)).await;
+
+        for uri in [
+            "/songs/1?transpose=up",
+            "/setlists/1/export.pdf?transpose=2.5",
+        ] {
+            let response = test::call_service(
+                &app,
+                TestRequest::get()
+                    .uri(uri)
+                    .insert_header(bearer(&token))
+                    .to_request(),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", uri);
+            let body: ApiError = test::read_body_json(response).await;
+            assert_eq!(body.code, ErrorCode::BadRequest);
+        }
+
+        // Valid transpose gets through to the handler.
+        let response = test::call_service(
+            &app,
+            TestRequest::get()
+                .uri("/songs/1?transpose=+2")
+                .insert_header(bearer(&token))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,284 @@
+//! Songbook PDF rendered from ChordPro bodies, chords are printed above the
+//! lyrics they are played on.
+
+use setlistrs_types::chordpro::{self, ChordProSong, Line, Section, SectionKind, Segment};
+
+use crate::pdf::{Align, Document, Font, Span};
+
+const BOOK_TITLE_SIZE: f32 = 28.0;
+const TOC_SIZE: f32 = 12.0;
+const TITLE_SIZE: f32 = 20.0;
+const META_SIZE: f32 = 10.0;
+const LABEL_SIZE: f32 = 11.0;
+const CHORD_SIZE: f32 = 10.0;
+const LYRICS_SIZE: f32 = 11.0;
+const MIN_LYRICS_SIZE: f32 = 7.0;
+/// Space kept between two chords which are closer than their width.
+const CHORD_GAP: f32 = 4.0;
+
+pub struct SongbookSong<'a> {
+    pub title: &'a str,
+    /// Extra line under the title, e.g. setlist specific capo or note.
+    pub details: Option<String>,
+    pub chords: &'a str,
+    pub chordpro: Option<&'a str>,
+}
+
+/// Every song starts on its own page. Table of contents is added when there
+/// is more than one song.
+pub fn render(title: &str, songs: &[SongbookSong]) -> Vec<u8> {
+    if songs.len() < 2 {
+        let mut document = Document::new(title);
+        for song in songs {
+            render_song(&mut document, song);
+        }
+        return document.finish(true);
+    }
+
+    // Songs are laid out once to learn where they start, table of contents
+    // always takes the same number of pages regardless of numbers in it.
+    let mut placeholder = Document::new(title);
+    contents(&mut placeholder, title, songs, &vec![0; songs.len()]);
+    let contents_pages = placeholder.page_count();
+
+    let mut songs_only = Document::new(title);
+    let first_pages: Vec<usize> = songs
+        .iter()
+        .map(|song| {
+            let first_page = contents_pages + songs_only.page_count() + 1;
+            render_song(&mut songs_only, song);
+            first_page
+        })
+        .collect();
+
+    let mut document = Document::new(title);
+    contents(&mut document, title, songs, &first_pages);
+    for song in songs {
+        render_song(&mut document, song);
+    }
+
+    document.finish(true)
+}
+
+fn contents(document: &mut Document, title: &str, songs: &[SongbookSong], first_pages: &[usize]) {
+    document.new_page();
+    document.paragraph(title, Font::Bold, BOOK_TITLE_SIZE, Align::Center);
+    document.space(TOC_SIZE);
+    for (index, (song, first_page)) in songs.iter().zip(first_pages).enumerate() {
+        document.leader_line(
+            &format!("{}. {}", index + 1, song.title),
+            *first_page,
+            Font::Regular,
+            TOC_SIZE,
+        );
+    }
+}
+
+fn render_song(document: &mut Document, song: &SongbookSong) {
+    document.new_page();
+    document.paragraph(song.title, Font::Bold, TITLE_SIZE, Align::Left);
+
+    let parsed = song.chordpro.map(chordpro::parse);
+    if let Some(Ok(parsed)) = &parsed {
+        if let Some(subtitle) = parsed.subtitle.as_ref().or(parsed.artist.as_ref()) {
+            document.paragraph(subtitle, Font::Regular, META_SIZE, Align::Left);
+        }
+        let meta = meta(parsed);
+        if !meta.is_empty() {
+            document.paragraph(&meta, Font::Regular, META_SIZE, Align::Left);
+        }
+    }
+    if let Some(details) = &song.details {
+        document.paragraph(details, Font::Regular, META_SIZE, Align::Left);
+    }
+    document.space(LYRICS_SIZE);
+
+    match parsed {
+        Some(Ok(parsed)) => {
+            for section in parsed.sections.iter() {
+                render_section(document, section);
+            }
+        }
+        // Stored bodies are validated, but the song should still be printed
+        // with whatever there is when parser gets stricter one day.
+        Some(Err(_)) => {
+            for line in song.chordpro.unwrap_or_default().lines() {
+                mono_line(document, line);
+            }
+        }
+        None => document.paragraph(song.chords, Font::Bold, TITLE_SIZE, Align::Left),
+    }
+}
+
+fn meta(song: &ChordProSong) -> String {
+    [
+        song.key.as_ref().map(|key| format!("Key: {}", key)),
+        song.capo.map(|capo| format!("Capo: {}", capo)),
+        song.tempo.map(|tempo| format!("Tempo: {}", tempo)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<String>>()
+    .join(" · ")
+}
+
+fn render_section(document: &mut Document, section: &Section) {
+    let label = section.label.clone().or_else(|| match section.kind {
+        SectionKind::Chorus => Some("Chorus".to_string()),
+        SectionKind::Bridge => Some("Bridge".to_string()),
+        _ => None,
+    });
+    if let Some(label) = label {
+        // Heading should not be left alone at the bottom of a page.
+        document.ensure_space(
+            Document::line_height(LABEL_SIZE)
+                + Document::line_height(CHORD_SIZE)
+                + Document::line_height(LYRICS_SIZE),
+        );
+        document.paragraph(&label, Font::Bold, LABEL_SIZE, Align::Left);
+    }
+
+    for line in section.lines.iter() {
+        match line {
+            Line::Lyrics(segments) => lyrics_line(document, segments),
+            Line::Comment(comment) => {
+                document.paragraph(comment, Font::Regular, META_SIZE, Align::Left)
+            }
+            Line::Preformatted(text) => mono_line(document, text),
+            Line::Empty => document.space(Document::line_height(LYRICS_SIZE) / 2.0),
+        }
+    }
+    document.space(Document::line_height(LYRICS_SIZE) / 2.0);
+}
+
+/// Chords and lyrics of a segment start at the same `x`. When chord is wider
+/// than its lyrics, the next segment is moved right so chords do not overlap.
+fn lyrics_line(document: &mut Document, segments: &[Segment]) {
+    let has_chords = segments.iter().any(|segment| segment.chord.is_some());
+    let line_width = positions(segments, 1.0)
+        .last()
+        .zip(segments.last())
+        .map(|(x, segment)| x + segment_width(segment, 1.0))
+        .unwrap_or_default();
+    // Lines which do not fit are set smaller rather than cut at page edge.
+    let scale = (Document::content_width() / line_width).clamp(MIN_LYRICS_SIZE / LYRICS_SIZE, 1.0);
+    let positions = positions(segments, scale);
+
+    if has_chords {
+        document.ensure_space(
+            Document::line_height(CHORD_SIZE * scale) + Document::line_height(LYRICS_SIZE * scale),
+        );
+        let chords: Vec<Span> = segments
+            .iter()
+            .zip(positions.iter())
+            .filter_map(|(segment, x)| {
+                Some(Span {
+                    x: *x,
+                    text: segment.chord.as_deref()?,
+                    font: Font::Bold,
+                    size: CHORD_SIZE * scale,
+                })
+            })
+            .collect();
+        document.line(&chords);
+    }
+
+    let lyrics: Vec<Span> = segments
+        .iter()
+        .zip(positions.iter())
+        .map(|(segment, x)| Span {
+            x: *x,
+            text: &segment.lyrics,
+            font: Font::Regular,
+            size: LYRICS_SIZE * scale,
+        })
+        .collect();
+    document.line(&lyrics);
+}
+
+fn positions(segments: &[Segment], scale: f32) -> Vec<f32> {
+    let mut x = 0.0;
+    segments
+        .iter()
+        .map(|segment| {
+            let position = x;
+            x += segment_width(segment, scale);
+            position
+        })
+        .collect()
+}
+
+fn segment_width(segment: &Segment, scale: f32) -> f32 {
+    let lyrics_width = Font::Regular.width(&segment.lyrics, LYRICS_SIZE * scale);
+    let chord_width = segment
+        .chord
+        .as_deref()
+        .map(|chord| Font::Bold.width(chord, CHORD_SIZE * scale) + CHORD_GAP * scale)
+        .unwrap_or_default();
+
+    lyrics_width.max(chord_width)
+}
+
+fn mono_line(document: &mut Document, text: &str) {
+    document.line(&[Span {
+        x: 0.0,
+        text,
+        font: Font::Mono,
+        size: META_SIZE,
+    }]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::pdf_pages;
+
+    const CHORDPRO: &str = "{title: Wonderwall}\n{key: F#m}\n{capo: 2}\n\n\
+        {start_of_verse: Verse 1}\n[Em7]Today is [G]gonna be the day\n{end_of_verse}\n\
+        {start_of_chorus}\nAnd [C]all the roads we [D]have to walk are [Em7]winding\n{end_of_chorus}\n\
+        {start_of_tab}\ne|--3--2--0--|\n{end_of_tab}\n";
+
+    fn song<'a>(title: &'a str, chordpro: Option<&'a str>) -> SongbookSong<'a> {
+        SongbookSong {
+            title,
+            details: Some("Capo 2".to_string()),
+            chords: "Em7 G Dsus4 A7sus4",
+            chordpro,
+        }
+    }
+
+    #[test]
+    fn renders_chordpro_song() {
+        assert_eq!(
+            pdf_pages(&render("Wonderwall", &[song("Wonderwall", Some(CHORDPRO))])),
+            1
+        );
+
+        let long = CHORDPRO.repeat(20).replace("{title: Wonderwall}", "");
+        assert!(pdf_pages(&render("Long", &[song("Long", Some(&long))])) > 1);
+    }
+
+    #[test]
+    fn renders_song_without_chordpro() {
+        assert_eq!(pdf_pages(&render("Song", &[song("Song", None)])), 1);
+    }
+
+    #[test]
+    fn renders_invalid_chordpro_as_written() {
+        assert_eq!(
+            pdf_pages(&render("Song", &[song("Song", Some("[G la la\n{soc}"))])),
+            1
+        );
+    }
+
+    #[test]
+    fn adds_contents_before_songs() {
+        let songs = [song("One", Some(CHORDPRO)), song("Two", None)];
+        assert_eq!(pdf_pages(&render("Friday", &songs)), 3);
+    }
+
+    #[test]
+    fn renders_empty_songbook() {
+        assert_eq!(pdf_pages(&render("Empty", &[])), 1);
+    }
+}
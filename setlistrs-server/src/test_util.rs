@@ -0,0 +1,91 @@
+//! Database and app for tests of repositories and handlers.
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::web::Data;
+use actix_web::App;
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::SqlitePool;
+
+use crate::user::new_token;
+use crate::{band, error, setlist, song, user};
+
+/// Migrated in-memory database. It lives in a single connection, so the pool
+/// must not hand out more of them.
+pub async fn pool() -> SqlitePool {
+    pool_with(SqlitePoolOptions::new()).await
+}
+
+/// Like [`pool`], with options given by the test, e.g. to hook into the
+/// connection.
+pub async fn pool_with(options: SqlitePoolOptions) -> SqlitePool {
+    let pool = options
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::migrate!().run(&pool).await.unwrap();
+
+    pool
+}
+
+/// Creates user with a session, gives their id and session token.
+pub async fn user(pool: &SqlitePool, username: &str) -> (i64, String) {
+    let user_id =
+        sqlx::query("INSERT INTO users(username, password_hash, created_at) VALUES(?, '', 0)")
+            .bind(username)
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+    let token = new_token();
+    sqlx::query("INSERT INTO sessions(token, user_id, created_at, expires_at) VALUES(?, ?, 0, ?)")
+        .bind(&token)
+        .bind(user_id)
+        .bind(i64::MAX)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    (user_id, token)
+}
+
+/// App configured the way `main` does it.
+pub fn app(
+    pool: &SqlitePool,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    App::new()
+        .app_data(Data::new(pool.clone()))
+        .app_data(error::json_config())
+        .app_data(error::query_config())
+        .configure(song::init)
+        .configure(setlist::init)
+        .configure(user::init)
+        .configure(band::init)
+}
+
+pub fn bearer(token: &str) -> (&'static str, String) {
+    ("Authorization", format!("Bearer {}", token))
+}
+
+/// Number of pages of rendered PDF, read from its page tree.
+pub fn pdf_pages(pdf: &[u8]) -> usize {
+    assert!(pdf.starts_with(b"%PDF-"), "not a PDF");
+    let count = pdf
+        .windows(7)
+        .position(|window| window == b"/Count ")
+        .expect("PDF has page tree");
+
+    pdf[count + 7..]
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .fold(0, |pages, digit| pages * 10 + (digit - b'0') as usize)
+}
@@ -0,0 +1,8 @@
+mod password;
+mod repository;
+mod routes;
+mod session;
+
+pub use password::{lacks_password, set_password};
+pub use routes::init;
+pub use session::{new_token, CurrentUser};
@@ -0,0 +1,87 @@
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use sqlx::SqlitePool;
+
+use crate::user::repository;
+
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+/// Users without a valid hash, e.g. admin created by migration, can not login.
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    match PasswordHash::new(password_hash) {
+        Ok(parsed_hash) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed_hash)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Hash of no real password. Login verifies against it when the username is
+/// unknown, so the response takes as long as for a wrong password.
+pub fn dummy_hash() -> &'static str {
+    static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+    DUMMY_HASH.get_or_init(|| hash_password("not a password").unwrap_or_default())
+}
+
+/// Whether existing user has no valid hash and so can not login, like admin
+/// created by migration before a password is set.
+pub async fn lacks_password(pool: &SqlitePool, username: &str) -> Result<bool> {
+    Ok(match repository::find_by_username(pool, username).await? {
+        Some((_, password_hash)) => PasswordHash::new(&password_hash).is_err(),
+        None => false,
+    })
+}
+
+/// Replaces password of existing user, returns `false` when there is no such user.
+pub async fn set_password(pool: &SqlitePool, username: &str, password: &str) -> Result<bool> {
+    let password_hash = hash_password(password).map_err(|e| anyhow!("{}", e))?;
+    repository::update_password_hash(pool, username, &password_hash).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_hashed_password() {
+        let password_hash = hash_password("long enough").unwrap();
+        assert!(verify_password("long enough", &password_hash));
+        assert!(!verify_password("long enough!", &password_hash));
+        assert_ne!(hash_password("long enough").unwrap(), password_hash);
+    }
+
+    #[test]
+    fn rejects_unusable_hashes() {
+        assert!(!verify_password("", ""));
+        assert!(!verify_password("not a password", "not a hash"));
+        assert!(PasswordHash::new(dummy_hash()).is_ok());
+    }
+
+    #[actix_web::test]
+    async fn finds_users_without_password() {
+        let pool = crate::test_util::pool().await;
+        // As created by migration when there is content from before accounts.
+        sqlx::query(
+            "INSERT INTO users(username, password_hash, created_at) VALUES('admin', '', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert!(lacks_password(&pool, "admin").await.unwrap());
+        assert!(!lacks_password(&pool, "nobody").await.unwrap());
+
+        assert!(set_password(&pool, "admin", "long enough").await.unwrap());
+        assert!(!lacks_password(&pool, "admin").await.unwrap());
+        assert!(!set_password(&pool, "nobody", "long enough").await.unwrap());
+    }
+}
@@ -0,0 +1,169 @@
+use anyhow::Result;
+use chrono::Utc;
+use setlistrs_types::User;
+use sqlx::{query, SqlitePool};
+
+pub enum RegisterOutcome {
+    Registered(User),
+    UsernameTaken,
+}
+
+pub async fn create(
+    pool: &SqlitePool,
+    username: &str,
+    password_hash: &str,
+) -> Result<RegisterOutcome> {
+    let mut transaction = pool.begin().await?;
+
+    if query!(
+        r#"
+SELECT id
+FROM users
+WHERE username = ?
+        "#,
+        username
+    )
+    .fetch_optional(&mut transaction)
+    .await?
+    .is_some()
+    {
+        return Ok(RegisterOutcome::UsernameTaken);
+    }
+
+    let created_at = Utc::now().timestamp();
+    let user_id = query!(
+        r#"
+INSERT INTO users(username, password_hash, created_at)
+VALUES (?, ?, ?)
+        "#,
+        username,
+        password_hash,
+        created_at
+    )
+    .execute(&mut transaction)
+    .await?
+    .last_insert_rowid();
+
+    transaction.commit().await?;
+
+    Ok(RegisterOutcome::Registered(User {
+        id: user_id,
+        username: username.to_string(),
+    }))
+}
+
+/// Returns user together with hash of their password.
+pub async fn find_by_username(pool: &SqlitePool, username: &str) -> Result<Option<(User, String)>> {
+    Ok(query!(
+        r#"
+SELECT id AS "id!", username, password_hash
+FROM users
+WHERE username = ?
+        "#,
+        username
+    )
+    .map(|user| {
+        (
+            User {
+                id: user.id,
+                username: user.username,
+            },
+            user.password_hash,
+        )
+    })
+    .fetch_optional(pool)
+    .await?)
+}
+
+/// Returns `false` when there is no user with given name.
+pub async fn update_password_hash(
+    pool: &SqlitePool,
+    username: &str,
+    password_hash: &str,
+) -> Result<bool> {
+    Ok(query!(
+        r#"
+UPDATE users
+SET password_hash = ?
+WHERE username = ?
+        "#,
+        password_hash,
+        username
+    )
+    .execute(pool)
+    .await?
+    .rows_affected()
+        > 0)
+}
+
+pub async fn create_session(
+    pool: &SqlitePool,
+    user_id: i64,
+    token: &str,
+    expires_at: i64,
+) -> Result<()> {
+    let created_at = Utc::now().timestamp();
+    query!(
+        r#"
+INSERT INTO sessions(token, user_id, created_at, expires_at)
+VALUES (?, ?, ?, ?)
+        "#,
+        token,
+        user_id,
+        created_at,
+        expires_at
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Finds owner of a session which has not expired yet.
+pub async fn find_session_user(pool: &SqlitePool, token: &str) -> Result<Option<User>> {
+    let now = Utc::now().timestamp();
+    Ok(query!(
+        r#"
+SELECT u.id, u.username
+FROM users u, sessions s
+WHERE s.token = ?
+AND s.expires_at > ?
+AND u.id = s.user_id
+        "#,
+        token,
+        now
+    )
+    .map(|user| User {
+        id: user.id,
+        username: user.username,
+    })
+    .fetch_optional(pool)
+    .await?)
+}
+
+pub async fn delete_expired_sessions(pool: &SqlitePool) -> Result<u64> {
+    let now = Utc::now().timestamp();
+    Ok(query!(
+        r#"
+DELETE FROM sessions
+WHERE expires_at <= ?
+        "#,
+        now
+    )
+    .execute(pool)
+    .await?
+    .rows_affected())
+}
+
+pub async fn delete_session(pool: &SqlitePool, token: &str) -> Result<u64> {
+    Ok(query!(
+        r#"
+DELETE FROM sessions
+WHERE token = ?
+        "#,
+        token
+    )
+    .execute(pool)
+    .await?
+    .rows_affected())
+}
@@ -0,0 +1,269 @@
+use actix_web::{
+    cookie::{time, Cookie, SameSite},
+    get, post,
+    web::{self, Data, Json, ServiceConfig},
+    HttpResponse, Responder, ResponseError,
+};
+use anyhow::anyhow;
+use setlistrs_types::error::{ApiError, FieldError};
+use setlistrs_types::{Credentials, Session};
+use sqlx::SqlitePool;
+
+use crate::error::error_response;
+use crate::user::password::{dummy_hash, hash_password, verify_password};
+use crate::user::repository::{self, RegisterOutcome};
+use crate::user::session::{self, CurrentUser, SESSION_COOKIE, SESSION_DAYS};
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+const MAX_USERNAME_LENGTH: usize = 32;
+
+pub fn init(config: &mut ServiceConfig) {
+    config
+        .service(register)
+        .service(login)
+        .service(logout)
+        .service(me);
+}
+
+#[post("/register")]
+async fn register(pool: Data<SqlitePool>, credentials: Json<Credentials>) -> impl Responder {
+    let Credentials { username, password } = credentials.into_inner();
+    let username = username.trim().to_string();
+    if let Err(e) = validate_credentials(&username, &password) {
+        return ApiError::validation(vec![e]).error_response();
+    }
+
+    // Hashing takes a while on purpose, it should not block other requests.
+    let password_hash = match web::block(move || hash_password(&password)).await {
+        Ok(Ok(password_hash)) => password_hash,
+        Ok(Err(e)) => return error_response(anyhow!("Hashing password failed, {}", e)),
+        Err(e) => return error_response(e.into()),
+    };
+
+    match repository::create(pool.get_ref(), &username, &password_hash).await {
+        Ok(RegisterOutcome::Registered(user)) => HttpResponse::Created().json(user),
+        Ok(RegisterOutcome::UsernameTaken) => {
+            ApiError::conflict(format!("Username {} is already taken", username)).error_response()
+        }
+        Err(e) => error_response(e),
+    }
+}
+#[post("/login")]
+async fn login(pool: Data<SqlitePool>, credentials: Json<Credentials>) -> impl Responder {
+    let Credentials { username, password } = credentials.into_inner();
+
+    let (user, password_hash) =
+        match repository::find_by_username(pool.get_ref(), username.trim()).await {
+            Ok(Some((user, password_hash))) => (Some(user), Some(password_hash)),
+            Ok(None) => (None, None),
+            Err(e) => return error_response(e),
+        };
+
+    // Unknown users are verified too, so response time does not tell which
+    // usernames exist.
+    let verified = web::block(move || match password_hash {
+        Some(password_hash) => verify_password(&password, &password_hash),
+        None => verify_password(&password, dummy_hash()),
+    })
+    .await;
+    let user = match (user, verified) {
+        (Some(user), Ok(true)) => user,
+        (_, Ok(_)) => return invalid_credentials(),
+        (_, Err(e)) => return error_response(e.into()),
+    };
+
+    // Sessions are cleaned up here rather than by a background job.
+    if let Err(e) = repository::delete_expired_sessions(pool.get_ref()).await {
+        return error_response(e);
+    }
+    let token = session::new_token();
+    if let Err(e) =
+        repository::create_session(pool.get_ref(), user.id, &token, session::expires_at()).await
+    {
+        return error_response(e);
+    }
+
+    let cookie = Cookie::build(SESSION_COOKIE, token.clone())
+        .path("/")
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::days(SESSION_DAYS))
+        .finish();
+
+    HttpResponse::Ok()
+        .cookie(cookie)
+        .json(Session { token, user })
+}
+#[post("/logout")]
+async fn logout(pool: Data<SqlitePool>, current_user: CurrentUser) -> impl Responder {
+    match repository::delete_session(pool.get_ref(), &current_user.token).await {
+        Ok(_) => {
+            let mut cookie = Cookie::build(SESSION_COOKIE, "")
+                .path("/")
+                .http_only(true)
+                .secure(true)
+                .finish();
+            cookie.make_removal();
+            HttpResponse::NoContent().cookie(cookie).finish()
+        }
+        Err(e) => error_response(e),
+    }
+}
+#[get("/me")]
+async fn me(current_user: CurrentUser) -> impl Responder {
+    HttpResponse::Ok().json(current_user.user)
+}
+
+fn invalid_credentials() -> HttpResponse {
+    ApiError::unauthorized("Invalid username or password").error_response()
+}
+
+fn validate_credentials(username: &str, password: &str) -> Result<(), FieldError> {
+    if username.is_empty() || username.chars().count() > MAX_USERNAME_LENGTH {
+        return Err(FieldError::new(
+            "username",
+            format!(
+                "Username has to have between 1 and {} characters",
+                MAX_USERNAME_LENGTH
+            ),
+        ));
+    }
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(FieldError::new(
+            "password",
+            format!(
+                "Password has to have at least {} characters",
+                MIN_PASSWORD_LENGTH
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use setlistrs_types::error::ErrorCode;
+    use setlistrs_types::User;
+
+    use super::*;
+    use crate::test_util::{self, bearer};
+
+    fn credentials(username: &str, password: &str) -> Credentials {
+        Credentials {
+            username: username.to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn logs_in_registered_user() {
+        let pool = test_util::pool().await;
+        let app = test::init_service(test_util::app(&pool)).await;
+
+        let response = test::call_service(
+            &app,
+            TestRequest::post()
+                .uri("/register")
+                .set_json(credentials("ann", "long enough"))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        for (username, password) in [("ann", "wrong password"), ("bob", "long enough")] {
+            let response = test::call_service(
+                &app,
+                TestRequest::post()
+                    .uri("/login")
+                    .set_json(credentials(username, password))
+                    .to_request(),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            let body: ApiError = test::read_body_json(response).await;
+            assert_eq!(body.message, "Invalid username or password");
+        }
+
+        let response = test::call_service(
+            &app,
+            TestRequest::post()
+                .uri("/login")
+                .set_json(credentials(" ann ", "long enough"))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let cookie = response
+            .response()
+            .cookies()
+            .find(|cookie| cookie.name() == SESSION_COOKIE)
+            .unwrap();
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.http_only(), Some(true));
+        let session: Session = test::read_body_json(response).await;
+        assert_eq!(session.user.username, "ann");
+
+        let response = test::call_service(
+            &app,
+            TestRequest::get()
+                .uri("/me")
+                .insert_header(bearer(&session.token))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let user: User = test::read_body_json(response).await;
+        assert_eq!(user, session.user);
+    }
+
+    #[actix_web::test]
+    async fn rejects_and_deletes_expired_sessions() {
+        let pool = test_util::pool().await;
+        let (user_id, token) = test_util::user(&pool, "ann").await;
+        let password_hash = hash_password("long enough").unwrap();
+        sqlx::query("UPDATE users SET password_hash = ? WHERE id = ?")
+            .bind(password_hash)
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE sessions SET expires_at = 0 WHERE token = ?")
+            .bind(&token)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let app = test::init_service(test_util::app(&pool)).await;
+
+        let response = test::call_service(
+            &app,
+            TestRequest::get()
+                .uri("/me")
+                .insert_header(bearer(&token))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let body: ApiError = test::read_body_json(response).await;
+        assert_eq!(body.code, ErrorCode::Unauthorized);
+
+        let response = test::call_service(
+            &app,
+            TestRequest::post()
+                .uri("/login")
+                .set_json(credentials("ann", "long enough"))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let tokens: Vec<String> = sqlx::query_scalar("SELECT token FROM sessions")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let session: Session = test::read_body_json(response).await;
+        assert_eq!(tokens, [session.token]);
+    }
+}
@@ -0,0 +1,67 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use actix_web::{http::header, web::Data, FromRequest, HttpRequest};
+use chrono::{Duration, Utc};
+use setlistrs_types::error::ApiError;
+use setlistrs_types::User;
+use sqlx::SqlitePool;
+
+use crate::error::api_error;
+use crate::user::repository;
+
+pub const SESSION_COOKIE: &str = "setlistrs_session";
+pub const SESSION_DAYS: i64 = 30;
+
+/// User of the session the request was sent with. Token is read from
+/// `Authorization: Bearer <token>` header or from the session cookie,
+/// handlers taking it respond with 401 to anonymous requests.
+pub struct CurrentUser {
+    pub user: User,
+    pub token: String,
+}
+
+impl FromRequest for CurrentUser {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
+        let token = session_token(req);
+        let pool = req.app_data::<Data<SqlitePool>>().cloned();
+
+        Box::pin(async move {
+            let token = token.ok_or_else(|| ApiError::unauthorized("Login required"))?;
+            let pool = pool.ok_or_else(ApiError::internal)?;
+
+            match repository::find_session_user(pool.get_ref(), &token).await {
+                Ok(Some(user)) => Ok(CurrentUser { user, token }),
+                Ok(None) => Err(ApiError::unauthorized("Session expired, login again").into()),
+                Err(e) => Err(api_error(e).into()),
+            }
+        })
+    }
+}
+
+fn session_token(req: &HttpRequest) -> Option<String> {
+    let bearer = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string());
+
+    bearer.or_else(|| {
+        req.cookie(SESSION_COOKIE)
+            .map(|cookie| cookie.value().to_string())
+    })
+}
+
+/// Random 256 bit token, hex encoded.
+pub fn new_token() -> String {
+    let bytes: [u8; 32] = rand::random();
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn expires_at() -> i64 {
+    (Utc::now() + Duration::days(SESSION_DAYS)).timestamp()
+}
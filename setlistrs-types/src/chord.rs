@@ -0,0 +1,633 @@
+//! Chord model used for transposing songs between keys.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::chordpro;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Letter {
+    C,
+    D,
+    E,
+    F,
+    G,
+    A,
+    B,
+}
+
+impl Letter {
+    fn semitone(self) -> i32 {
+        match self {
+            Letter::C => 0,
+            Letter::D => 2,
+            Letter::E => 4,
+            Letter::F => 5,
+            Letter::G => 7,
+            Letter::A => 9,
+            Letter::B => 11,
+        }
+    }
+
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'C' => Some(Letter::C),
+            'D' => Some(Letter::D),
+            'E' => Some(Letter::E),
+            'F' => Some(Letter::F),
+            'G' => Some(Letter::G),
+            'A' => Some(Letter::A),
+            'B' => Some(Letter::B),
+            _ => None,
+        }
+    }
+
+    fn as_char(self) -> char {
+        match self {
+            Letter::C => 'C',
+            Letter::D => 'D',
+            Letter::E => 'E',
+            Letter::F => 'F',
+            Letter::G => 'G',
+            Letter::A => 'A',
+            Letter::B => 'B',
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Accidental {
+    Natural,
+    Sharp,
+    Flat,
+}
+
+impl Accidental {
+    fn semitone(self) -> i32 {
+        match self {
+            Accidental::Natural => 0,
+            Accidental::Sharp => 1,
+            Accidental::Flat => -1,
+        }
+    }
+}
+
+/// Whether accidentals are written as sharps or flats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Spelling {
+    Sharps,
+    Flats,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Note {
+    pub letter: Letter,
+    pub accidental: Accidental,
+}
+
+impl Note {
+    /// Pitch class, 0 for C up to 11 for B.
+    pub fn pitch_class(&self) -> i32 {
+        (self.letter.semitone() + self.accidental.semitone()).rem_euclid(12)
+    }
+
+    pub fn from_pitch_class(pitch_class: i32, spelling: Spelling) -> Self {
+        let (letter, accidental) = match (pitch_class.rem_euclid(12), spelling) {
+            (0, _) => (Letter::C, Accidental::Natural),
+            (1, Spelling::Sharps) => (Letter::C, Accidental::Sharp),
+            (1, Spelling::Flats) => (Letter::D, Accidental::Flat),
+            (2, _) => (Letter::D, Accidental::Natural),
+            (3, Spelling::Sharps) => (Letter::D, Accidental::Sharp),
+            (3, Spelling::Flats) => (Letter::E, Accidental::Flat),
+            (4, _) => (Letter::E, Accidental::Natural),
+            (5, _) => (Letter::F, Accidental::Natural),
+            (6, Spelling::Sharps) => (Letter::F, Accidental::Sharp),
+            (6, Spelling::Flats) => (Letter::G, Accidental::Flat),
+            (7, _) => (Letter::G, Accidental::Natural),
+            (8, Spelling::Sharps) => (Letter::G, Accidental::Sharp),
+            (8, Spelling::Flats) => (Letter::A, Accidental::Flat),
+            (9, _) => (Letter::A, Accidental::Natural),
+            (10, Spelling::Sharps) => (Letter::A, Accidental::Sharp),
+            (10, Spelling::Flats) => (Letter::B, Accidental::Flat),
+            _ => (Letter::B, Accidental::Natural),
+        };
+
+        Self { letter, accidental }
+    }
+
+    pub fn transpose(&self, semitones: i32, spelling: Spelling) -> Self {
+        Self::from_pitch_class(self.pitch_class() + semitones.rem_euclid(12), spelling)
+    }
+
+    /// Parses note from the beginning of `text`, returns it with remaining
+    /// text. Lowercase letter is accepted only with `lowercase`.
+    fn parse_prefix(text: &str, lowercase: bool) -> Option<(Self, bool, &str)> {
+        let mut chars = text.chars();
+        let first = chars.next()?;
+        let letter = if lowercase {
+            Letter::from_char(first.to_ascii_uppercase())?
+        } else {
+            Letter::from_char(first)?
+        };
+        let rest = chars.as_str();
+        let (accidental, rest) = match rest.chars().next() {
+            Some('#') | Some('♯') => {
+                (Accidental::Sharp, &rest[rest.chars().next()?.len_utf8()..])
+            }
+            Some('b') | Some('♭') => (Accidental::Flat, &rest[rest.chars().next()?.len_utf8()..]),
+            _ => (Accidental::Natural, rest),
+        };
+
+        Some((
+            Self { letter, accidental },
+            first.is_ascii_lowercase(),
+            rest,
+        ))
+    }
+}
+
+impl fmt::Display for Note {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.letter.as_char())?;
+        match self.accidental {
+            Accidental::Natural => Ok(()),
+            Accidental::Sharp => write!(f, "#"),
+            Accidental::Flat => write!(f, "b"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quality {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Chord {
+    pub root: Note,
+    pub quality: Quality,
+    /// Quality as written, e.g. `m` or `min` for minor, `+` or `aug` for
+    /// augmented; kept on output.
+    pub quality_text: String,
+    /// Everything between quality and slash bass, e.g. `7`, `maj7`, `sus4`, `add9`.
+    pub extensions: String,
+    pub bass: Option<Note>,
+    /// Root was written in lowercase, e.g. `b` in `b G D A`; kept on output.
+    pub lowercase: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChordParseError(pub String);
+
+impl fmt::Display for ChordParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not a chord", self.0)
+    }
+}
+
+impl std::error::Error for ChordParseError {}
+
+impl FromStr for Chord {
+    type Err = ChordParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse(text, false)
+    }
+}
+
+impl Chord {
+    /// Like `str::parse`, but also accepts lowercase root, e.g. `b` for B
+    /// minor. Only for text known to hold chords, `a` or `e` are words too.
+    pub fn parse_any_case(text: &str) -> Result<Self, ChordParseError> {
+        Self::parse(text, true)
+    }
+
+    fn parse(text: &str, lowercase: bool) -> Result<Self, ChordParseError> {
+        let error = || ChordParseError(text.to_string());
+        let (root, lowercase, rest) = Note::parse_prefix(text, lowercase).ok_or_else(error)?;
+
+        let (body, bass) = match rest.rsplit_once('/') {
+            Some((body, bass)) => match Note::parse_prefix(bass, false) {
+                Some((bass, _, "")) => (body, Some(bass)),
+                _ => return Err(error()),
+            },
+            None => (rest, None),
+        };
+
+        let (quality, extensions) = if let Some(extensions) = body.strip_prefix("min") {
+            (Quality::Minor, extensions)
+        } else if body.starts_with("maj") {
+            (Quality::Major, body)
+        } else if let Some(extensions) = body.strip_prefix('m') {
+            (Quality::Minor, extensions)
+        } else if let Some(extensions) = body.strip_prefix("dim") {
+            (Quality::Diminished, extensions)
+        } else if let Some(extensions) = body.strip_prefix('°') {
+            (Quality::Diminished, extensions)
+        } else if let Some(extensions) = body.strip_prefix("aug") {
+            (Quality::Augmented, extensions)
+        } else if let Some(extensions) = body.strip_prefix('+') {
+            (Quality::Augmented, extensions)
+        } else {
+            (Quality::Major, body)
+        };
+
+        if !is_extension(extensions) {
+            return Err(error());
+        }
+
+        Ok(Self {
+            root,
+            quality,
+            quality_text: body[..body.len() - extensions.len()].to_string(),
+            extensions: extensions.to_string(),
+            bass,
+            lowercase,
+        })
+    }
+}
+
+/// Whether `text` consists only of chord extensions: intervals like `7` or
+/// `13`, `m`, `maj`, `min`, `dim`, `aug`, `sus` and `add`, alterations like
+/// `b5` or `#9` and parenthesised groups of them, e.g. `(b9,#11)`. Words
+/// such as `ridge` in `Bridge` are not extensions.
+fn is_extension(mut text: &str) -> bool {
+    const WORDS: [&str; 7] = ["maj", "min", "dim", "aug", "sus", "add", "m"];
+
+    while !text.is_empty() {
+        if let Some(group) = text.strip_prefix('(') {
+            match group.split_once(')') {
+                Some((inner, rest))
+                    if inner
+                        .split(',')
+                        .all(|part| !part.trim().is_empty() && is_extension(part.trim())) =>
+                {
+                    text = rest;
+                    continue;
+                }
+                _ => return false,
+            }
+        }
+
+        let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits > 0 {
+            text = &text[digits..];
+        } else if let Some(rest) = WORDS.iter().find_map(|word| text.strip_prefix(word)) {
+            text = rest;
+        } else if let Some(rest) = text.strip_prefix(['b', '#', '♭', '♯', '+', '-']) {
+            // Alteration is always followed by the interval it alters.
+            if !rest.starts_with(|c: char| c.is_ascii_digit()) {
+                return false;
+            }
+            text = rest;
+        } else {
+            return false;
+        }
+    }
+
+    true
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let root = self.root.to_string();
+        if self.lowercase {
+            write!(f, "{}", root.to_lowercase())?;
+        } else {
+            write!(f, "{}", root)?;
+        }
+        write!(f, "{}{}", self.quality_text, self.extensions)?;
+        if let Some(bass) = &self.bass {
+            write!(f, "/{}", bass)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Chord {
+    pub fn transpose(&self, semitones: i32, spelling: Spelling) -> Self {
+        Self {
+            root: self.root.transpose(semitones, spelling),
+            quality: self.quality,
+            quality_text: self.quality_text.clone(),
+            extensions: self.extensions.clone(),
+            bass: self.bass.map(|bass| bass.transpose(semitones, spelling)),
+            lowercase: self.lowercase,
+        }
+    }
+
+    /// Spelling conventionally used for the key this chord is the tonic of,
+    /// e.g. flats for `F` or `Dm`, sharps for `E` or `C#m`. Lowercase root
+    /// is read as minor key.
+    pub fn key_spelling(&self) -> Spelling {
+        let pitch_class = self.root.pitch_class();
+        let flat_keys: &[i32] = match (self.quality, self.lowercase) {
+            // Cm, Ebm, Fm, Gm, Bbm and Dm
+            (Quality::Minor | Quality::Diminished, _) | (_, true) => &[0, 2, 3, 5, 7, 10],
+            // Db, Eb, F, Ab, Bb
+            _ => &[1, 3, 5, 8, 10],
+        };
+
+        if flat_keys.contains(&pitch_class) {
+            Spelling::Flats
+        } else {
+            Spelling::Sharps
+        }
+    }
+}
+
+/// Transposes key of the song by `semitones`, returning the new key together
+/// with spelling all transposed chords should use.
+pub fn transpose_key(key: &Chord, semitones: i32) -> (Chord, Spelling) {
+    let moved = key.transpose(semitones, Spelling::Sharps);
+    let spelling = moved.key_spelling();
+
+    (key.transpose(semitones, spelling), spelling)
+}
+
+/// Parser for chords on a line of progression. Lowercase roots like `b` in
+/// `b G D A` are read only when there are no other words on the line, so
+/// `a` or `e` in a note such as `play a G then e` stay as they are. Labels
+/// like `Intro:` are not counted as words.
+fn line_parser(line: &str) -> fn(&str) -> Result<Chord, ChordParseError> {
+    let has_words = line.split_whitespace().any(|token| {
+        token.chars().filter(|c| c.is_alphabetic()).count() > 1
+            && !token.ends_with(':')
+            && Chord::parse_any_case(token).is_err()
+    });
+
+    if has_words {
+        |token| token.parse()
+    } else {
+        Chord::parse_any_case
+    }
+}
+
+/// Transposes whitespace separated chord progression like `b G D A`.
+/// Tokens which are not chords, e.g. `|` or `x2`, are left as they are.
+pub fn transpose_progression(progression: &str, semitones: i32, spelling: Spelling) -> String {
+    progression
+        .split('\n')
+        .map(|line| transpose_line(line, semitones, spelling))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn transpose_line(line: &str, semitones: i32, spelling: Spelling) -> String {
+    let parse = line_parser(line);
+    let mut transposed = String::with_capacity(line.len());
+    let mut token = String::new();
+
+    for c in line.chars().chain(std::iter::once(' ')) {
+        if c.is_whitespace() {
+            if !token.is_empty() {
+                match parse(&token) {
+                    Ok(chord) => {
+                        transposed.push_str(&chord.transpose(semitones, spelling).to_string())
+                    }
+                    Err(_) => transposed.push_str(&token),
+                }
+                token.clear();
+            }
+            transposed.push(c);
+        } else {
+            token.push(c);
+        }
+    }
+    transposed.pop();
+
+    transposed
+}
+
+/// First token of the progression that is a chord.
+pub fn first_chord(progression: &str) -> Option<Chord> {
+    progression.lines().find_map(|line| {
+        let parse = line_parser(line);
+        line.split_whitespace().find_map(|token| parse(token).ok())
+    })
+}
+
+/// Transposes both chord progression and ChordPro body of a song by
+/// `semitones`. Spelling follows the new key, which is read from `{key}`,
+/// from the first ChordPro chord or from the first chord of the progression.
+pub fn transpose_song(
+    chords: &str,
+    chordpro: Option<&str>,
+    semitones: i32,
+) -> (String, Option<String>) {
+    if semitones.rem_euclid(12) == 0 {
+        return (chords.to_string(), chordpro.map(str::to_string));
+    }
+
+    let spelling = chordpro
+        .and_then(chordpro::key)
+        .or_else(|| first_chord(chords))
+        .map(|key| transpose_key(&key, semitones).1)
+        .unwrap_or(Spelling::Sharps);
+
+    (
+        transpose_progression(chords, semitones, spelling),
+        chordpro.map(|body| chordpro::transpose(body, semitones, spelling)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(text: &str) -> Chord {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_quality_extensions_and_bass() {
+        let parsed = chord("F#m7b5/C");
+        assert_eq!(
+            parsed.root,
+            Note {
+                letter: Letter::F,
+                accidental: Accidental::Sharp
+            }
+        );
+        assert_eq!(parsed.quality, Quality::Minor);
+        assert_eq!(parsed.extensions, "7b5");
+        assert_eq!(
+            parsed.bass,
+            Some(Note {
+                letter: Letter::C,
+                accidental: Accidental::Natural
+            })
+        );
+        assert!(!parsed.lowercase);
+
+        assert_eq!(chord("Bb").root.accidental, Accidental::Flat);
+        assert_eq!(chord("Cmaj7").quality, Quality::Major);
+        assert_eq!(chord("Cmaj7").extensions, "maj7");
+        assert_eq!(chord("Ebdim").quality, Quality::Diminished);
+        assert_eq!(chord("G+").quality, Quality::Augmented);
+        assert!(Chord::parse_any_case("b").unwrap().lowercase);
+    }
+
+    #[test]
+    fn accepts_lowercase_root_only_when_asked() {
+        for text in ["a", "e", "b", "d/F#"] {
+            assert!(text.parse::<Chord>().is_err(), "{} parsed as chord", text);
+            assert_eq!(Chord::parse_any_case(text).unwrap().to_string(), text);
+        }
+        assert!(Chord::parse_any_case("D/f#").is_err());
+    }
+
+    #[test]
+    fn keeps_quality_as_written() {
+        for text in ["G+", "Gaug7", "B°", "Bdim7", "Amin", "Am7", "Cmaj7"] {
+            assert_eq!(chord(text).to_string(), text);
+        }
+        assert_eq!(chord("G+").transpose(2, Spelling::Sharps).to_string(), "A+");
+        assert_eq!(
+            chord("B°7").transpose(1, Spelling::Sharps).to_string(),
+            "C°7"
+        );
+    }
+
+    #[test]
+    fn accepts_chord_extensions() {
+        for text in [
+            "C7",
+            "Csus4",
+            "C7sus2",
+            "Cadd9",
+            "Cm(maj7)",
+            "C7(b9,#11)",
+            "C13",
+            "Dm7/C",
+        ] {
+            assert_eq!(chord(text).to_string(), text);
+        }
+    }
+
+    #[test]
+    fn rejects_words() {
+        for text in [
+            "Bridge", "End", "Chorus", "Am:", "Gb-", "Ebb", "Be", "Add", "C(", "C7()", "D/H",
+        ] {
+            assert!(text.parse::<Chord>().is_err(), "{} parsed as chord", text);
+        }
+    }
+
+    #[test]
+    fn transposes_with_spelling() {
+        assert_eq!(chord("C").transpose(1, Spelling::Sharps).to_string(), "C#");
+        assert_eq!(chord("C").transpose(1, Spelling::Flats).to_string(), "Db");
+        assert_eq!(
+            chord("Am7").transpose(-2, Spelling::Sharps).to_string(),
+            "Gm7"
+        );
+        assert_eq!(
+            Chord::parse_any_case("b")
+                .unwrap()
+                .transpose(3, Spelling::Sharps)
+                .to_string(),
+            "d"
+        );
+    }
+
+    #[test]
+    fn transposes_slash_chords() {
+        assert_eq!(
+            chord("D/F#").transpose(2, Spelling::Sharps).to_string(),
+            "E/G#"
+        );
+        assert_eq!(
+            chord("G/B").transpose(3, Spelling::Flats).to_string(),
+            "Bb/D"
+        );
+    }
+
+    #[test]
+    fn leaves_non_chord_words_in_progression() {
+        assert_eq!(
+            transpose_progression("Intro: G D | Bridge Em C End", 2, Spelling::Sharps),
+            "Intro: A E | Bridge F#m D End"
+        );
+        assert_eq!(
+            transpose_progression("b G  D A x2", -1, Spelling::Flats),
+            "bb Gb  Db Ab x2"
+        );
+        assert_eq!(
+            transpose_progression("Verse: b G D A\nChorus: G D e C", 2, Spelling::Sharps),
+            "Verse: c# A E B\nChorus: A E f# D"
+        );
+    }
+
+    #[test]
+    fn leaves_prose_in_progression() {
+        assert_eq!(
+            transpose_progression("Capo 2, play a G then e\ne G", 2, Spelling::Sharps),
+            "Capo 2, play a A then e\nf# A"
+        );
+        assert_eq!(
+            first_chord("a capella intro\nb G D A").unwrap().to_string(),
+            "b"
+        );
+    }
+
+    #[test]
+    fn transposes_song_in_spelling_of_new_key() {
+        assert_eq!(
+            transpose_song("G C D", None, 3),
+            ("Bb Eb F".to_string(), None)
+        );
+        assert_eq!(
+            transpose_song("G C D", None, 4),
+            ("B E F#".to_string(), None)
+        );
+        assert_eq!(
+            transpose_song("e C G D", None, 1),
+            ("f Db Ab Eb".to_string(), None)
+        );
+        assert_eq!(
+            transpose_song("G C D", None, 12),
+            ("G C D".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn transposes_chordpro_in_spelling_of_new_key() {
+        // Key comes from `{key}` even though the progression starts elsewhere.
+        assert_eq!(
+            transpose_song("A D E", Some("{key: Dm}\n[Dm]La [A7]la"), 5),
+            (
+                "D G A".to_string(),
+                Some("{key: Gm}\n[Gm]La [D7]la".to_string())
+            )
+        );
+        assert_eq!(
+            transpose_song("C F", Some("{key: C}\n[C]La [F/A]la"), -1),
+            (
+                "B E".to_string(),
+                Some("{key: B}\n[B]La [E/G#]la".to_string())
+            )
+        );
+        assert_eq!(
+            transpose_song("", Some("[F]La [Bb]la"), 1),
+            ("".to_string(), Some("[F#]La [B]la".to_string()))
+        );
+        assert_eq!(
+            transpose_song("", Some("[F]La [Bb]la"), -2),
+            ("".to_string(), Some("[Eb]La [Ab]la".to_string()))
+        );
+    }
+
+    #[test]
+    fn key_spelling_follows_key() {
+        assert_eq!(chord("F").key_spelling(), Spelling::Flats);
+        assert_eq!(chord("Dm").key_spelling(), Spelling::Flats);
+        assert_eq!(chord("E").key_spelling(), Spelling::Sharps);
+        assert_eq!(transpose_key(&chord("C"), 5).0.to_string(), "F");
+        assert_eq!(transpose_key(&chord("C"), 1).0.to_string(), "Db");
+    }
+}
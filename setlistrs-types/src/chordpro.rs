@@ -0,0 +1,569 @@
+//! Parser for [ChordPro](https://www.chordpro.org/chordpro/) song bodies.
+//!
+//! Only the subset that is useful for a band's setlist is understood: song
+//! metadata (`{title}`, `{subtitle}`, `{artist}`, `{key}`, `{capo}`, `{tempo}`),
+//! comments, verse/chorus/bridge/tab sections and inline `[G]` chords.
+//! Directives outside of that subset are skipped, so bodies written for other
+//! ChordPro tools still parse.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::chord::{Chord, Spelling};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ChordProSong {
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub artist: Option<String>,
+    pub key: Option<String>,
+    pub capo: Option<u32>,
+    pub tempo: Option<u32>,
+    pub sections: Vec<Section>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Section {
+    pub kind: SectionKind,
+    pub label: Option<String>,
+    pub lines: Vec<Line>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SectionKind {
+    /// Lines outside of any `{start_of_*}` block.
+    Plain,
+    Verse,
+    Chorus,
+    Bridge,
+    Tab,
+}
+
+impl SectionKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SectionKind::Plain => "plain",
+            SectionKind::Verse => "verse",
+            SectionKind::Chorus => "chorus",
+            SectionKind::Bridge => "bridge",
+            SectionKind::Tab => "tab",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Line {
+    Lyrics(Vec<Segment>),
+    Comment(String),
+    /// Line kept exactly as written, used inside tab sections.
+    Preformatted(String),
+    Empty,
+}
+
+/// Piece of lyrics line, `chord` is played at the start of `lyrics`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub chord: Option<String>,
+    pub lyrics: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based number of offending line.
+    pub line: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnclosedChord,
+    EmptyChord,
+    UnclosedDirective,
+    InvalidNumber { directive: String, value: String },
+    MissingValue { directive: String },
+    NestedSection { open: SectionKind },
+    UnexpectedSectionEnd { expected: Option<SectionKind> },
+    UnclosedSection { open: SectionKind },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ParseErrorKind::UnclosedChord => write!(f, "chord is missing closing `]`"),
+            ParseErrorKind::EmptyChord => write!(f, "chord `[]` is empty"),
+            ParseErrorKind::UnclosedDirective => write!(f, "directive is missing closing `}}`"),
+            ParseErrorKind::InvalidNumber { directive, value } => {
+                write!(f, "`{}` expects a number, got `{}`", directive, value)
+            }
+            ParseErrorKind::MissingValue { directive } => {
+                write!(f, "`{}` requires a value", directive)
+            }
+            ParseErrorKind::NestedSection { open } => {
+                write!(f, "section starts before {} section is closed", open.name())
+            }
+            ParseErrorKind::UnexpectedSectionEnd { expected } => match expected {
+                Some(expected) => write!(f, "expected end of {} section", expected.name()),
+                None => write!(f, "section end without its start"),
+            },
+            ParseErrorKind::UnclosedSection { open } => {
+                write!(f, "{} section is never closed", open.name())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub fn parse(body: &str) -> Result<ChordProSong, ParseError> {
+    let mut song = ChordProSong::default();
+    let mut current = Section {
+        kind: SectionKind::Plain,
+        label: None,
+        lines: Vec::new(),
+    };
+    let mut open_since: usize = 0;
+
+    for (index, raw_line) in body.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.trim_end();
+        let error = |kind| ParseError {
+            line: line_number,
+            kind,
+        };
+
+        if current.kind == SectionKind::Tab && !is_directive(line) {
+            current.lines.push(Line::Preformatted(line.to_string()));
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            current.lines.push(Line::Empty);
+            continue;
+        }
+
+        // `#` at the very start of a line is a source comment, never rendered.
+        if line.starts_with('#') {
+            continue;
+        }
+
+        if !is_directive(line) {
+            current
+                .lines
+                .push(Line::Lyrics(parse_lyrics(line).map_err(error)?));
+            continue;
+        }
+
+        let (name, value) = parse_directive(line).map_err(error)?;
+        match name.as_str() {
+            "title" | "t" => song.title = Some(required(&name, value).map_err(error)?),
+            "subtitle" | "st" => song.subtitle = Some(required(&name, value).map_err(error)?),
+            "artist" => song.artist = Some(required(&name, value).map_err(error)?),
+            "key" => song.key = Some(required(&name, value).map_err(error)?),
+            "capo" => song.capo = Some(number(&name, value).map_err(error)?),
+            "tempo" => song.tempo = Some(number(&name, value).map_err(error)?),
+            "comment" | "c" | "comment_italic" | "ci" | "comment_box" | "cb" | "highlight" => {
+                current
+                    .lines
+                    .push(Line::Comment(required(&name, value).map_err(error)?));
+            }
+            "chorus" => current
+                .lines
+                .push(Line::Comment(value.unwrap_or_else(|| "Chorus".to_string()))),
+            _ => {
+                if let Some(kind) = section_start(&name) {
+                    if current.kind != SectionKind::Plain {
+                        return Err(error(ParseErrorKind::NestedSection { open: current.kind }));
+                    }
+                    push_section(&mut song, current);
+                    current = Section {
+                        kind,
+                        label: value,
+                        lines: Vec::new(),
+                    };
+                    open_since = line_number;
+                } else if let Some(kind) = section_end(&name) {
+                    if current.kind != kind {
+                        return Err(error(ParseErrorKind::UnexpectedSectionEnd {
+                            expected: Some(current.kind).filter(|kind| *kind != SectionKind::Plain),
+                        }));
+                    }
+                    push_section(&mut song, current);
+                    current = Section {
+                        kind: SectionKind::Plain,
+                        label: None,
+                        lines: Vec::new(),
+                    };
+                }
+                // Any other directive is not relevant for us and is skipped.
+            }
+        }
+    }
+
+    if current.kind != SectionKind::Plain {
+        return Err(ParseError {
+            line: open_since,
+            kind: ParseErrorKind::UnclosedSection { open: current.kind },
+        });
+    }
+    push_section(&mut song, current);
+
+    Ok(song)
+}
+
+/// Key of the song, taken from `{key}` or from the first chord when the
+/// directive is missing.
+pub fn key(body: &str) -> Option<Chord> {
+    let song = parse(body).ok()?;
+    if let Some(key) = song.key.and_then(|key| Chord::parse_any_case(&key).ok()) {
+        return Some(key);
+    }
+
+    song.sections
+        .iter()
+        .flat_map(|section| section.lines.iter())
+        .filter_map(|line| match line {
+            Line::Lyrics(segments) => Some(segments),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|segment| Chord::parse_any_case(segment.chord.as_ref()?).ok())
+}
+
+/// Rewrites inline chords and `{key}` of the body, everything else including
+/// tab sections is kept byte for byte. Chords that can not be parsed, e.g.
+/// `[N.C.]`, are left as they are.
+pub fn transpose(body: &str, semitones: i32, spelling: Spelling) -> String {
+    let transpose_chord = |chord: &str| match Chord::parse_any_case(chord.trim()) {
+        Ok(chord) => chord.transpose(semitones, spelling).to_string(),
+        Err(_) => chord.to_string(),
+    };
+    let mut in_tab = false;
+
+    body.split('\n')
+        .map(|line| {
+            if is_directive(line) {
+                let (name, value) = match parse_directive(line) {
+                    Ok(directive) => directive,
+                    Err(_) => return line.to_string(),
+                };
+                if section_start(&name) == Some(SectionKind::Tab) {
+                    in_tab = true;
+                } else if section_end(&name) == Some(SectionKind::Tab) {
+                    in_tab = false;
+                }
+                return match (name.as_str(), value) {
+                    ("key", Some(key)) => {
+                        let indent = &line[..line.len() - line.trim_start().len()];
+                        // `\r` of CRLF line endings is part of the line here.
+                        let line_ending = if line.ends_with('\r') { "\r" } else { "" };
+                        format!(
+                            "{}{{key: {}}}{}",
+                            indent,
+                            transpose_chord(&key),
+                            line_ending
+                        )
+                    }
+                    _ => line.to_string(),
+                };
+            }
+            if in_tab || line.starts_with('#') {
+                return line.to_string();
+            }
+
+            let mut transposed = String::with_capacity(line.len());
+            let mut rest = line;
+            while let Some(opening) = rest.find('[') {
+                let Some(closing) = rest[opening..].find(']') else {
+                    break;
+                };
+                transposed.push_str(&rest[..=opening]);
+                transposed.push_str(&transpose_chord(&rest[opening + 1..opening + closing]));
+                transposed.push(']');
+                rest = &rest[opening + closing + 1..];
+            }
+            transposed.push_str(rest);
+
+            transposed
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_directive(line: &str) -> bool {
+    line.trim_start().starts_with('{')
+}
+
+fn push_section(song: &mut ChordProSong, mut section: Section) {
+    if section.kind == SectionKind::Plain {
+        while section.lines.last() == Some(&Line::Empty) {
+            section.lines.pop();
+        }
+        while section.lines.first() == Some(&Line::Empty) {
+            section.lines.remove(0);
+        }
+        if section.lines.is_empty() {
+            return;
+        }
+    }
+
+    song.sections.push(section);
+}
+
+fn parse_directive(line: &str) -> Result<(String, Option<String>), ParseErrorKind> {
+    let line = line.trim();
+    let inner = line
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .ok_or(ParseErrorKind::UnclosedDirective)?;
+
+    let (name, value) = match inner.find([':', ' ']) {
+        Some(position) => (&inner[..position], Some(inner[position + 1..].trim())),
+        None => (inner, None),
+    };
+
+    Ok((
+        name.trim().to_lowercase(),
+        value.filter(|value| !value.is_empty()).map(str::to_string),
+    ))
+}
+
+fn required(directive: &str, value: Option<String>) -> Result<String, ParseErrorKind> {
+    value.ok_or_else(|| ParseErrorKind::MissingValue {
+        directive: directive.to_string(),
+    })
+}
+
+fn number(directive: &str, value: Option<String>) -> Result<u32, ParseErrorKind> {
+    let value = required(directive, value)?;
+    value.parse().map_err(|_| ParseErrorKind::InvalidNumber {
+        directive: directive.to_string(),
+        value,
+    })
+}
+
+fn section_start(directive: &str) -> Option<SectionKind> {
+    match directive {
+        "start_of_verse" | "sov" => Some(SectionKind::Verse),
+        "start_of_chorus" | "soc" => Some(SectionKind::Chorus),
+        "start_of_bridge" | "sob" => Some(SectionKind::Bridge),
+        "start_of_tab" | "sot" => Some(SectionKind::Tab),
+        _ => None,
+    }
+}
+
+fn section_end(directive: &str) -> Option<SectionKind> {
+    match directive {
+        "end_of_verse" | "eov" => Some(SectionKind::Verse),
+        "end_of_chorus" | "eoc" => Some(SectionKind::Chorus),
+        "end_of_bridge" | "eob" => Some(SectionKind::Bridge),
+        "end_of_tab" | "eot" => Some(SectionKind::Tab),
+        _ => None,
+    }
+}
+
+fn parse_lyrics(line: &str) -> Result<Vec<Segment>, ParseErrorKind> {
+    let mut segments = Vec::new();
+    let mut rest = line;
+
+    // Text before the first chord is sung without a chord change.
+    let leading = match rest.find('[') {
+        Some(position) => &rest[..position],
+        None => rest,
+    };
+    if !leading.is_empty() {
+        segments.push(Segment {
+            chord: None,
+            lyrics: leading.to_string(),
+        });
+    }
+    rest = &rest[leading.len()..];
+
+    while let Some(after_bracket) = rest.strip_prefix('[') {
+        let closing = after_bracket
+            .find(']')
+            .ok_or(ParseErrorKind::UnclosedChord)?;
+        let chord = after_bracket[..closing].trim();
+        if chord.is_empty() {
+            return Err(ParseErrorKind::EmptyChord);
+        }
+        rest = &after_bracket[closing + 1..];
+
+        let lyrics = match rest.find('[') {
+            Some(position) => &rest[..position],
+            None => rest,
+        };
+        segments.push(Segment {
+            chord: Some(chord.to_string()),
+            lyrics: lyrics.to_string(),
+        });
+        rest = &rest[lyrics.len()..];
+    }
+
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lyrics(segments: &[(Option<&str>, &str)]) -> Line {
+        Line::Lyrics(
+            segments
+                .iter()
+                .map(|(chord, lyrics)| Segment {
+                    chord: chord.map(str::to_string),
+                    lyrics: lyrics.to_string(),
+                })
+                .collect(),
+        )
+    }
+
+    fn error_kind(body: &str) -> ParseErrorKind {
+        parse(body).unwrap_err().kind
+    }
+
+    #[test]
+    fn parses_metadata() {
+        let song =
+            parse("{title: Wonderwall}\n{st: Live}\n{key: F#m}\n{capo: 2}\n{tempo: 87}").unwrap();
+
+        assert_eq!(song.title.as_deref(), Some("Wonderwall"));
+        assert_eq!(song.subtitle.as_deref(), Some("Live"));
+        assert_eq!(song.key.as_deref(), Some("F#m"));
+        assert_eq!(song.capo, Some(2));
+        assert_eq!(song.tempo, Some(87));
+        assert!(song.sections.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_metadata() {
+        assert_eq!(
+            error_kind("{capo: two}"),
+            ParseErrorKind::InvalidNumber {
+                directive: "capo".to_string(),
+                value: "two".to_string()
+            }
+        );
+        assert_eq!(
+            error_kind("{title}"),
+            ParseErrorKind::MissingValue {
+                directive: "title".to_string()
+            }
+        );
+        assert_eq!(
+            error_kind("{title: Song"),
+            ParseErrorKind::UnclosedDirective
+        );
+    }
+
+    #[test]
+    fn parses_sections_and_chords() {
+        let song = parse(
+            "[G]Today is [D]gonna be\n\n{start_of_chorus: Chorus}\nAnd [Em]all the roads\n{end_of_chorus}\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            song.sections,
+            [
+                Section {
+                    kind: SectionKind::Plain,
+                    label: None,
+                    lines: vec![lyrics(&[(Some("G"), "Today is "), (Some("D"), "gonna be")])],
+                },
+                Section {
+                    kind: SectionKind::Chorus,
+                    label: Some("Chorus".to_string()),
+                    lines: vec![lyrics(&[(None, "And "), (Some("Em"), "all the roads")])],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn pairs_section_starts_and_ends() {
+        assert!(parse("{soc}\nLa\n{eoc}\n{sov}\nLa\n{eov}").is_ok());
+        assert_eq!(
+            parse("Intro\n{start_of_chorus}\nLa"),
+            Err(ParseError {
+                line: 2,
+                kind: ParseErrorKind::UnclosedSection {
+                    open: SectionKind::Chorus
+                }
+            })
+        );
+        assert_eq!(
+            error_kind("{start_of_chorus}\n{end_of_verse}"),
+            ParseErrorKind::UnexpectedSectionEnd {
+                expected: Some(SectionKind::Chorus)
+            }
+        );
+        assert_eq!(
+            error_kind("{end_of_chorus}"),
+            ParseErrorKind::UnexpectedSectionEnd { expected: None }
+        );
+        assert_eq!(
+            error_kind("{start_of_chorus}\n{start_of_verse}"),
+            ParseErrorKind::NestedSection {
+                open: SectionKind::Chorus
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_unclosed_and_empty_chords() {
+        assert_eq!(
+            parse("La la\n[G la la"),
+            Err(ParseError {
+                line: 2,
+                kind: ParseErrorKind::UnclosedChord
+            })
+        );
+        assert_eq!(error_kind("[]la"), ParseErrorKind::EmptyChord);
+    }
+
+    #[test]
+    fn skips_unknown_directives_and_source_comments() {
+        let song = parse("{x_custom: anything}\n{new_page}\n# not rendered\nLa").unwrap();
+        assert_eq!(song.sections[0].lines, [lyrics(&[(None, "La")])]);
+    }
+
+    #[test]
+    fn keeps_tab_lines_as_written() {
+        let song = parse("{start_of_tab}\ne|--[3]--|\n{end_of_tab}").unwrap();
+        assert_eq!(
+            song.sections[0].lines,
+            [Line::Preformatted("e|--[3]--|".to_string())]
+        );
+    }
+
+    #[test]
+    fn reads_key_from_directive_or_first_chord() {
+        assert_eq!(key("{key: Bb}\n[C]La").unwrap().to_string(), "Bb");
+        assert_eq!(key("La\n[Dm]La [G]la").unwrap().to_string(), "Dm");
+        assert_eq!(key("La la"), None);
+    }
+
+    #[test]
+    fn transposes_key_and_inline_chords() {
+        let body = "{title: Song}\n{key: G}\n[G]La [D/F#]la [N.C.]la\n{sot}\ne|--[3]--|\n{eot}";
+        assert_eq!(
+            transpose(body, 3, Spelling::Flats),
+            "{title: Song}\n{key: Bb}\n[Bb]La [F/A]la [N.C.]la\n{sot}\ne|--[3]--|\n{eot}"
+        );
+        assert_eq!(
+            transpose("{key: C}\n[C]La [C#m]la", 1, Spelling::Sharps),
+            "{key: C#}\n[C#]La [Dm]la"
+        );
+    }
+
+    #[test]
+    fn keeps_line_endings_when_transposing() {
+        assert_eq!(
+            transpose("{key: G}\r\n[G]La\r\n", 2, Spelling::Sharps),
+            "{key: A}\r\n[A]La\r\n"
+        );
+    }
+}
@@ -1,12 +1,17 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+pub mod chord;
+pub mod chordpro;
+pub mod error;
+pub mod validation;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct YTLink {
     pub url: String,
     pub display_title: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct YTLinkDetails {
     pub id: i64,
     pub url: String,
@@ -19,40 +24,355 @@ pub struct Song {
     pub source: Vec<YTLink>,
     pub cover: Option<Vec<YTLink>>,
     pub chords: String,
+    /// Lyrics with chords in ChordPro format, see [`chordpro::parse`].
+    pub chordpro: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct SongDetails {
     pub id: i64,
     pub name: String,
     pub source: Vec<YTLinkDetails>,
     pub cover: Option<Vec<YTLinkDetails>>,
     pub chords: String,
+    pub chordpro: Option<String>,
+}
+
+impl SongDetails {
+    pub fn transpose(&mut self, semitones: i32) {
+        (self.chords, self.chordpro) =
+            chord::transpose_song(&self.chords, self.chordpro.as_deref(), semitones);
+    }
+}
+
+impl From<YTLinkDetails> for YTLink {
+    fn from(yt_link: YTLinkDetails) -> Self {
+        Self {
+            url: yt_link.url,
+            display_title: yt_link.display_title,
+        }
+    }
 }
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SongPatch {
+    pub name: Option<String>,
+    pub source: Option<Vec<YTLink>>,
+    pub cover: Option<Vec<YTLink>>,
+    pub chords: Option<String>,
+    /// Empty string removes ChordPro body from song.
+    pub chordpro: Option<String>,
+}
+
+impl From<Song> for SongPatch {
+    fn from(song: Song) -> Self {
+        Self {
+            name: Some(song.name),
+            source: Some(song.source),
+            cover: Some(song.cover.unwrap_or_default()),
+            chords: Some(song.chords),
+            chordpro: Some(song.chordpro.unwrap_or_default()),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SongList {
     pub data: Vec<(i64, Song)>,
 }
 
+/// Song in trash, it can be restored until it is purged.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct DeletedSong {
+    pub id: i64,
+    pub name: String,
+    pub deleted_at: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeletedSongList {
+    pub data: Vec<DeletedSong>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct SongDeleteQuery {
+    /// Song is removed for good instead of being moved to trash.
+    #[serde(default)]
+    pub purge: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct GigDetails {
+    /// Day of the gig as `YYYY-MM-DD`.
+    pub gig_date: Option<String>,
+    /// Planned start of the gig as `HH:MM`.
+    pub start_time: Option<String>,
+    pub venue: Option<String>,
+    pub notes: Option<String>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Setlist {
     pub display_title: String,
+    #[serde(flatten)]
+    pub gig: GigDetails,
     pub songs: Vec<(i64, SetlistSong)>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct SetlistSong {
     pub display_title: String,
+    /// Chords of the song as played in this setlist, overrides are applied.
     pub chords: String,
+    pub chordpro: Option<String>,
+    #[serde(default)]
+    pub overrides: SetlistSongOverrides,
+    /// Song was moved to trash, it stays in setlist until it is purged.
+    #[serde(default)]
+    pub deleted: bool,
+}
+
+impl SetlistSong {
+    pub fn transpose(&mut self, semitones: i32) {
+        (self.chords, self.chordpro) =
+            chord::transpose_song(&self.chords, self.chordpro.as_deref(), semitones);
+    }
+}
+
+/// Setlist specific version of a song, the song itself stays untouched.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct SetlistSongOverrides {
+    /// Replaces chords of the song.
+    pub chords: Option<String>,
+    /// Replaces ChordPro body of the song.
+    pub chordpro: Option<String>,
+    /// Semitones the song is moved by, applied after custom chords.
+    pub transpose: i32,
+    pub capo: Option<u32>,
+    pub note: Option<String>,
+}
+
+/// Fields left as `None` are not changed, empty strings and capo `0` clear them.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SetlistSongOverridesPatch {
+    pub chords: Option<String>,
+    pub chordpro: Option<String>,
+    pub transpose: Option<i32>,
+    pub capo: Option<u32>,
+    pub note: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct NewSetlist {
     pub display_title: String,
+    #[serde(flatten)]
+    pub gig: GigDetails,
     pub songs: Vec<i64>,
 }
+
+/// Fields left as `None` are not changed, empty strings clear gig details.
+#[derive(Serialize, Deserialize, Default)]
+pub struct SetlistPatch {
+    pub display_title: Option<String>,
+    #[serde(flatten)]
+    pub gig: GigDetails,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NewSetlistSong {
+    pub song_id: i64,
+    pub position: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SetlistOrder {
+    pub songs: Vec<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct SetlistSummary {
+    pub display_title: String,
+    #[serde(flatten)]
+    pub gig: GigDetails,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SetlistList {
-    pub data: Vec<(i64, String)>,
+    pub data: Vec<(i64, SetlistSummary)>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct SetlistListQuery {
+    /// Lists archived setlists, e.g. of past gigs, instead of active ones.
+    #[serde(default)]
+    pub archived: bool,
+}
+
+/// Link which shows setlist read-only to anybody who has it, no login needed.
+/// `expires_at` is unix timestamp, links without it work until revoked.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SetlistShare {
+    pub token: String,
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SetlistShareList {
+    pub data: Vec<SetlistShare>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct NewSetlistShare {
+    pub expires_in_days: Option<u32>,
+}
+
+/// Query of song and setlist details, `transpose` is a signed number of
+/// semitones like `+2` or `-3`.
+#[derive(Serialize, Deserialize, Default)]
+pub struct TransposeQuery {
+    pub transpose: Option<String>,
+}
+
+impl TransposeQuery {
+    pub fn semitones(&self) -> Result<i32, std::num::ParseIntError> {
+        match &self.transpose {
+            // `+` of `?transpose=+2` is decoded as space.
+            Some(transpose) => transpose.trim().parse(),
+            None => Ok(0),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct SetlistExportQuery {
+    /// Adds a page with chords of every song after the stage sheet.
+    #[serde(default)]
+    pub chords: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+}
+
+/// Returned on login. Token is sent back either as session cookie, which
+/// server sets on its own, or as `Authorization: Bearer <token>` header.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Session {
+    pub token: String,
+    pub user: User,
+}
+
+/// Role of a member in a band, every role can do what the ones before it can.
+/// Viewers only read, editors change songs and setlists, owners also manage
+/// the band and its members.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Editor => "editor",
+            Role::Owner => "owner",
+        }
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "viewer" => Ok(Role::Viewer),
+            "editor" => Ok(Role::Editor),
+            "owner" => Ok(Role::Owner),
+            _ => Err(format!("Unknown role {}", value)),
+        }
+    }
+}
+
+/// Band as seen by one of its members.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Band {
+    pub id: i64,
+    pub name: String,
+    pub role: Role,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BandList {
+    pub data: Vec<Band>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NewBand {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct BandMember {
+    pub user: User,
+    pub role: Role,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct BandDetails {
+    pub band: Band,
+    pub members: Vec<BandMember>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BandMemberPatch {
+    pub role: Role,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NewBandInvite {
+    pub role: Role,
+}
+
+/// Code which lets one user join the band with given role, it can be used
+/// only once and stops working at `expires_at` (unix timestamp).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct BandInvite {
+    pub code: String,
+    pub role: Role,
+    pub expires_at: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BandInviteList {
+    pub data: Vec<BandInvite>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BandJoin {
+    pub code: String,
+}
+
+/// Picks library songs and setlists are read from and written to, personal
+/// library of the logged in user is used when `band` is not given.
+#[derive(Serialize, Deserialize, Default)]
+pub struct BandQuery {
+    pub band: Option<i64>,
 }
@@ -0,0 +1,473 @@
+//! Checks of request payloads which need no database, shared by the server
+//! and the app so both report the same field errors.
+
+use std::collections::HashSet;
+
+use crate::error::FieldError;
+use crate::{GigDetails, NewSetlist, NewSetlistShare, SetlistPatch, Song, SongPatch, YTLink};
+
+pub const MAX_NAME_LENGTH: usize = 128;
+pub const MAX_TITLE_LENGTH: usize = 128;
+pub const MAX_SHARE_EXPIRY_DAYS: u32 = 365;
+
+pub fn validate_song(song: &Song) -> Vec<FieldError> {
+    let mut field_errors = Vec::new();
+    validate_name(&song.name, &mut field_errors);
+    validate_links("source", &song.source, &mut field_errors);
+    if let Some(cover) = &song.cover {
+        validate_links("cover", cover, &mut field_errors);
+    }
+
+    field_errors
+}
+
+/// Only fields present in the patch are checked.
+pub fn validate_song_patch(song_patch: &SongPatch) -> Vec<FieldError> {
+    let mut field_errors = Vec::new();
+    if let Some(name) = &song_patch.name {
+        validate_name(name, &mut field_errors);
+    }
+    if let Some(source) = &song_patch.source {
+        validate_links("source", source, &mut field_errors);
+    }
+    if let Some(cover) = &song_patch.cover {
+        validate_links("cover", cover, &mut field_errors);
+    }
+
+    field_errors
+}
+
+/// Songs are not looked up here, unknown ids are reported by the server.
+pub fn validate_new_setlist(setlist: &NewSetlist) -> Vec<FieldError> {
+    let mut field_errors = Vec::new();
+    validate_title(&setlist.display_title, &mut field_errors);
+    field_errors.extend(validate_gig_details(&setlist.gig));
+
+    let mut seen = HashSet::new();
+    for song_id in &setlist.songs {
+        if !seen.insert(song_id) {
+            field_errors.push(FieldError::new(
+                "songs",
+                format!("Song {} is in setlist more than once", song_id),
+            ));
+        }
+    }
+
+    field_errors
+}
+
+pub fn validate_setlist_patch(setlist_patch: &SetlistPatch) -> Vec<FieldError> {
+    let mut field_errors = Vec::new();
+    if let Some(display_title) = &setlist_patch.display_title {
+        validate_title(display_title, &mut field_errors);
+    }
+    field_errors.extend(validate_gig_details(&setlist_patch.gig));
+
+    field_errors
+}
+
+/// Empty values clear gig details, so only filled ones are checked. Format is
+/// the one of `date` and `time` inputs of the browser.
+pub fn validate_gig_details(gig: &GigDetails) -> Vec<FieldError> {
+    let mut field_errors = Vec::new();
+    if let Some(gig_date) = gig.gig_date.as_deref().filter(|value| !value.is_empty()) {
+        match parse_date(gig_date) {
+            None => field_errors.push(FieldError::new(
+                "gig_date",
+                format!("Gig date {} is not in YYYY-MM-DD format", gig_date),
+            )),
+            Some((year, month, day)) if !date_exists(year, month, day) => field_errors.push(
+                FieldError::new("gig_date", format!("Gig date {} does not exist", gig_date)),
+            ),
+            Some(_) => {}
+        }
+    }
+    if let Some(start_time) = gig.start_time.as_deref().filter(|value| !value.is_empty()) {
+        if !is_time(start_time) {
+            field_errors.push(FieldError::new(
+                "start_time",
+                format!("Start time {} is not in HH:MM format", start_time),
+            ));
+        }
+    }
+
+    field_errors
+}
+
+/// Links without expiry are allowed, limited ones work from 1 day up to a year.
+pub fn validate_new_share(new_share: &NewSetlistShare) -> Vec<FieldError> {
+    let mut field_errors = Vec::new();
+    if let Some(days) = new_share.expires_in_days {
+        if !(1..=MAX_SHARE_EXPIRY_DAYS).contains(&days) {
+            field_errors.push(FieldError::new(
+                "expires_in_days",
+                format!("Link has to expire in 1 to {} days", MAX_SHARE_EXPIRY_DAYS),
+            ));
+        }
+    }
+
+    field_errors
+}
+
+/// Links are opened in browser, so only absolute http(s) URLs are accepted.
+pub fn is_http_url(url: &str) -> bool {
+    let rest = match url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+    {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+
+    !host.is_empty() && !url.chars().any(char::is_whitespace)
+}
+
+/// Link to a YouTube video, as song sources are played from YouTube.
+pub fn is_youtube_url(url: &str) -> bool {
+    if !is_http_url(url) {
+        return false;
+    }
+    let rest = url
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or_default();
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    host == "youtu.be" || host == "youtube.com" || host.ends_with(".youtube.com")
+}
+
+/// Year, month and day of date written as `YYYY-MM-DD`, the date does not
+/// have to exist.
+fn parse_date(text: &str) -> Option<(u32, u32, u32)> {
+    let parts: Vec<&str> = text.split('-').collect();
+    match parts[..] {
+        [year, month, day] if year.len() == 4 && month.len() == 2 && day.len() == 2 => {
+            Some((number(year)?, number(month)?, number(day)?))
+        }
+        _ => None,
+    }
+}
+
+fn date_exists(year: u32, month: u32, day: u32) -> bool {
+    let leap_year =
+        year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap_year => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    (1..=days_in_month).contains(&day)
+}
+
+/// Time of day written as `HH:MM` in 24 hour clock.
+fn is_time(text: &str) -> bool {
+    match text.split_once(':') {
+        Some((hours, minutes)) if hours.len() == 2 && minutes.len() == 2 => {
+            matches!(
+                (number(hours), number(minutes)),
+                (Some(0..=23), Some(0..=59))
+            )
+        }
+        _ => false,
+    }
+}
+
+/// Unsigned number made only of ASCII digits, unlike `parse` which accepts `+`.
+fn number(text: &str) -> Option<u32> {
+    if text.chars().all(|c| c.is_ascii_digit()) {
+        text.parse().ok()
+    } else {
+        None
+    }
+}
+
+fn validate_name(name: &str, field_errors: &mut Vec<FieldError>) {
+    if name.trim().is_empty() {
+        field_errors.push(FieldError::new("name", "Name is required"));
+    } else if name.chars().count() > MAX_NAME_LENGTH {
+        field_errors.push(FieldError::new(
+            "name",
+            format!("Name can have at most {} characters", MAX_NAME_LENGTH),
+        ));
+    }
+}
+
+fn validate_title(display_title: &str, field_errors: &mut Vec<FieldError>) {
+    if display_title.trim().is_empty() {
+        field_errors.push(FieldError::new("display_title", "Title is required"));
+    } else if display_title.chars().count() > MAX_TITLE_LENGTH {
+        field_errors.push(FieldError::new(
+            "display_title",
+            format!("Title can have at most {} characters", MAX_TITLE_LENGTH),
+        ));
+    }
+}
+
+/// Errors of single links are reported as e.g. `source[1].url`.
+fn validate_links(field: &str, yt_links: &[YTLink], field_errors: &mut Vec<FieldError>) {
+    for (index, yt_link) in yt_links.iter().enumerate() {
+        if !is_http_url(&yt_link.url) {
+            field_errors.push(FieldError::new(
+                format!("{}[{}].url", field, index),
+                format!("{} is not a valid http(s) URL", yt_link.url),
+            ));
+        }
+        let too_long_title = yt_link
+            .display_title
+            .as_ref()
+            .filter(|display_title| display_title.chars().count() > MAX_TITLE_LENGTH);
+        if too_long_title.is_some() {
+            field_errors.push(FieldError::new(
+                format!("{}[{}].display_title", field, index),
+                format!("Title can have at most {} characters", MAX_TITLE_LENGTH),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(name: &str, source: Vec<YTLink>) -> Song {
+        Song {
+            name: name.to_string(),
+            source,
+            cover: None,
+            chords: "G D".to_string(),
+            chordpro: None,
+        }
+    }
+
+    fn link(url: &str) -> YTLink {
+        YTLink {
+            url: url.to_string(),
+            display_title: None,
+        }
+    }
+
+    fn new_setlist(display_title: &str, songs: Vec<i64>) -> NewSetlist {
+        NewSetlist {
+            display_title: display_title.to_string(),
+            gig: Default::default(),
+            songs,
+        }
+    }
+
+    fn fields(field_errors: &[FieldError]) -> Vec<&str> {
+        field_errors
+            .iter()
+            .map(|field_error| field_error.field.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn accepts_valid_song() {
+        let valid = song("Wish You Were Here", vec![link("https://youtu.be/abc")]);
+        assert!(validate_song(&valid).is_empty());
+    }
+
+    #[test]
+    fn requires_song_name() {
+        for name in ["", "   ", "\t\n"] {
+            let field_errors = validate_song(&song(name, vec![]));
+            assert_eq!(field_errors, [FieldError::new("name", "Name is required")]);
+        }
+    }
+
+    #[test]
+    fn limits_song_name_length() {
+        let name = "a".repeat(MAX_NAME_LENGTH);
+        assert!(validate_song(&song(&name, vec![])).is_empty());
+
+        let field_errors = validate_song(&song(&format!("{}a", name), vec![]));
+        assert_eq!(fields(&field_errors), ["name"]);
+    }
+
+    #[test]
+    fn rejects_non_http_and_malformed_urls() {
+        for url in [
+            "ftp://youtu.be/abc",
+            "youtu.be/abc",
+            "https://",
+            "https:///abc",
+            "https://youtu.be/a bc",
+            "javascript:alert(1)",
+        ] {
+            assert!(!is_http_url(url), "{} accepted", url);
+        }
+        assert!(is_http_url("http://youtu.be"));
+        assert!(is_http_url("https://www.youtube.com/watch?v=abc"));
+
+        let mut invalid = song(
+            "Song",
+            vec![link("https://youtu.be/abc"), link("youtu.be/x")],
+        );
+        invalid.cover = Some(vec![link("ftp://youtu.be/y")]);
+        assert_eq!(
+            fields(&validate_song(&invalid)),
+            ["source[1].url", "cover[0].url"]
+        );
+    }
+
+    #[test]
+    fn accepts_only_youtube_hosts() {
+        for url in [
+            "https://youtu.be/abc",
+            "https://www.youtube.com/watch?v=abc",
+            "http://m.YouTube.com/watch?v=abc",
+            "https://music.youtube.com:443/watch?v=abc",
+        ] {
+            assert!(is_youtube_url(url), "{} rejected", url);
+        }
+        for url in [
+            "https://vimeo.com/123",
+            "https://notyoutube.com/watch?v=abc",
+            "https://youtube.com.example.com/watch",
+            "https://example.com/youtube.com",
+            "youtu.be/abc",
+        ] {
+            assert!(!is_youtube_url(url), "{} accepted", url);
+        }
+    }
+
+    #[test]
+    fn limits_link_title_length() {
+        let mut long_title = link("https://youtu.be/abc");
+        long_title.display_title = Some("a".repeat(MAX_TITLE_LENGTH + 1));
+        assert_eq!(
+            fields(&validate_song(&song("Song", vec![long_title]))),
+            ["source[0].display_title"]
+        );
+    }
+
+    #[test]
+    fn checks_only_fields_present_in_song_patch() {
+        assert!(validate_song_patch(&SongPatch::default()).is_empty());
+
+        let song_patch = SongPatch {
+            name: Some(" ".to_string()),
+            cover: Some(vec![link("not a url")]),
+            ..Default::default()
+        };
+        assert_eq!(
+            fields(&validate_song_patch(&song_patch)),
+            ["name", "cover[0].url"]
+        );
+    }
+
+    #[test]
+    fn limits_setlist_title() {
+        assert!(validate_new_setlist(&new_setlist("Friday gig", vec![1, 2])).is_empty());
+        assert_eq!(
+            validate_new_setlist(&new_setlist(" ", vec![])),
+            [FieldError::new("display_title", "Title is required")]
+        );
+
+        let title = "a".repeat(MAX_TITLE_LENGTH + 1);
+        assert_eq!(
+            fields(&validate_new_setlist(&new_setlist(&title, vec![]))),
+            ["display_title"]
+        );
+
+        let setlist_patch = SetlistPatch {
+            display_title: Some(title),
+            ..Default::default()
+        };
+        assert_eq!(
+            fields(&validate_setlist_patch(&setlist_patch)),
+            ["display_title"]
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_songs_in_new_setlist() {
+        let field_errors = validate_new_setlist(&new_setlist("Gig", vec![1, 2, 1]));
+        assert_eq!(
+            field_errors,
+            [FieldError::new(
+                "songs",
+                "Song 1 is in setlist more than once"
+            )]
+        );
+    }
+
+    #[test]
+    fn checks_gig_date_and_start_time() {
+        let gig = |gig_date: &str, start_time: &str| GigDetails {
+            gig_date: Some(gig_date.to_string()),
+            start_time: Some(start_time.to_string()),
+            ..Default::default()
+        };
+
+        for (gig_date, start_time) in [
+            ("2023-03-12", "20:30"),
+            ("2024-02-29", "00:00"),
+            ("2023-12-31", "23:59"),
+            ("", ""),
+        ] {
+            assert!(validate_gig_details(&gig(gig_date, start_time)).is_empty());
+        }
+        assert!(validate_gig_details(&GigDetails::default()).is_empty());
+
+        for (gig_date, start_time) in [
+            ("2023-02-29", "24:00"),
+            ("2023-13-01", "20:60"),
+            ("12.03.2023", "8:30"),
+            ("2023-3-12", "20:+1"),
+            ("tomorrow", "evening"),
+        ] {
+            assert_eq!(
+                fields(&validate_gig_details(&gig(gig_date, start_time))),
+                ["gig_date", "start_time"]
+            );
+        }
+
+        let mut setlist = new_setlist("Gig", vec![]);
+        setlist.gig = gig("2023-04-31", "");
+        assert_eq!(
+            validate_new_setlist(&setlist),
+            [FieldError::new(
+                "gig_date",
+                "Gig date 2023-04-31 does not exist"
+            )]
+        );
+        setlist.gig = gig("2023-4-30", "");
+        assert_eq!(
+            validate_new_setlist(&setlist),
+            [FieldError::new(
+                "gig_date",
+                "Gig date 2023-4-30 is not in YYYY-MM-DD format"
+            )]
+        );
+        let setlist_patch = SetlistPatch {
+            gig: gig("", "9pm"),
+            ..Default::default()
+        };
+        assert_eq!(
+            fields(&validate_setlist_patch(&setlist_patch)),
+            ["start_time"]
+        );
+    }
+
+    #[test]
+    fn limits_share_expiry() {
+        for expires_in_days in [None, Some(1), Some(MAX_SHARE_EXPIRY_DAYS)] {
+            let new_share = NewSetlistShare { expires_in_days };
+            assert!(validate_new_share(&new_share).is_empty());
+        }
+        for expires_in_days in [Some(0), Some(MAX_SHARE_EXPIRY_DAYS + 1), Some(u32::MAX)] {
+            let new_share = NewSetlistShare { expires_in_days };
+            assert_eq!(fields(&validate_new_share(&new_share)), ["expires_in_days"]);
+        }
+    }
+}